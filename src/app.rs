@@ -2,27 +2,16 @@
 // Based on tterm's proven architecture with Makepad integration
 
 use crate::types::*;
+use crate::clipboard::ClipboardReader;
+use crate::input::KeyEventType;
 use makepad_widgets::*;
-use makepad_widgets::event::ScrollEvent;
-use alacritty_terminal::grid::Dimensions;
-use alacritty_terminal::term::TermMode;
 
 live_design!{
     use link::theme::*;
     use link::shaders::*;
     use link::widgets::*;
-    
-    pub D2_CODING_FONT = {
-        font_family:{
-            latin = font("crate://self/assets/fonts/D2Coding.ttf", 0.0, 0.0),
-            cjk = font("crate://self/assets/fonts/D2Coding.ttf", 0.0, -0.5),
-        },
-        line_spacing: 1.0
-    } 
-    // Use system default font for now
-    // D2_CODING_FONT = {
-    //     path: dep("crate://self/assets/fonts/D2Coding.ttf") 
-    // }
+    use crate::terminal_panes::TerminalPanes;
+    use crate::tab_strip::TabStrip;
         
     App = {{App}} {
         ui: <Root>{
@@ -46,21 +35,8 @@ live_design!{
                             color: #404040,
                         }
                         
-                        // Tab buttons will be dynamically created
-                        tab1 = <Button> {
-                            text: "● Terminal 1",
-                            margin: {right: 4},
-                            padding: {left: 12, right: 12, top: 6, bottom: 6},
-                            draw_text: {
-                                color: #ffffff,
-                                text_style: {
-                                    font_size: 12.0,
-                                }
-                            },
-                            draw_bg: {
-                                color: #707070,
-                            }
-                        }
+                        // One tab per tab_order entry
+                        tab_strip = <TabStrip> {}
                         
                         // New tab button
                         new_tab_btn = <Button> {
@@ -82,29 +58,15 @@ live_design!{
                     
                     // Main terminal area  
                     terminal_area = <View> {
-                        height: 600,
+                        height: Fill,
                         width: Fill,
-                        scroll_bars: <ScrollBars> { 
-                            show_scroll_y: true,
-                        },
                         show_bg: true,
                         draw_bg: {
                             color: #1e1e1e,
                         }
                         
-                        terminal_display = <Label> {
-                            width: Fill,
-                            height: Fit,
-                            padding: {left: 12, right: 12, top: 12, bottom: 12},
-                            draw_text: {
-                                color: #c0c0c0,
-                                text_style:<D2_CODING_FONT>  {                                    
-                                    font_size: 9.0,
-                                    line_spacing: 1.0
-                                }
-                            },
-                            text: "",
-                        }
+                        // The active tab's split tree, one terminal view per pane
+                        terminal_panes = <TerminalPanes> {}
                     }
                     
                     // Status bar
@@ -139,11 +101,25 @@ pub struct App {
     #[live] ui: WidgetRef,
     #[rust] state: AppState,
     #[rust] pty_signal: SignalToUI,  // PTY 이벤트용 신호 (공식 API)
+    #[rust] config_signal: SignalToUI,  // config.toml 변경 신호
+    #[rust] config_watcher: Option<crate::config::ConfigWatcher>,
+    #[rust] clipboard: ClipboardReader<ClipboardRequest>,
+}
+
+/// What an asynchronous clipboard read is for
+enum ClipboardRequest {
+    /// AppAction::Paste into the focused terminal (and the broadcast targets)
+    Paste,
+    /// OSC 52 clipboard query from a program: the reply is formatted by alacritty
+    Osc52Load(u64, std::sync::Arc<dyn Fn(&str) -> String + Sync + Send>),
 }
 
 impl LiveRegister for App {
     fn live_register(cx: &mut Cx) { 
         crate::makepad_widgets::live_design(cx);
+        crate::terminal_view::live_design(cx);
+        crate::terminal_panes::live_design(cx);
+        crate::tab_strip::live_design(cx);
     }
 }
 
@@ -166,8 +142,9 @@ impl MatchEvent for App{
                 static mut WINDOW_RESIZE_DONE: bool = false;
                 unsafe {
                     DRAW_COUNT += 1;
-                    if DRAW_COUNT % 100 == 0 {
-                        println!("🎨 Received Draw event #{}", DRAW_COUNT);
+                    let draw_count = DRAW_COUNT;
+                    if draw_count.is_multiple_of(100) {
+                        println!("🎨 Received Draw event #{}", draw_count);
                     }
                     
                     // Skip window resize for now to avoid blocking startup
@@ -184,6 +161,7 @@ impl MatchEvent for App{
                 // Handle window resize to adjust terminal size
                 self.handle_window_resize(cx);
             }
+            Event::Actions(actions) => self.handle_actions(cx, actions),
             _ => {}
         }
         
@@ -204,6 +182,9 @@ impl MatchEvent for App{
         self.state = AppState::new();
         ::log::info!("📋 AppState initialized with {} terminals", self.state.terminals.len());
         
+        // Load user configuration before any terminal is spawned
+        self.load_config();
+        
         // Create the first tab automatically
         println!("📄 Creating initial tab - BASIC PRINTLN");
         Self::create_new_tab(&mut self.state, self.pty_signal.clone());
@@ -233,10 +214,19 @@ impl MatchEvent for App{
         // Always check for PTY events first - this replaces timer polling
         self.handle_pty_events(cx);
         
-        // Handle button/widget actions
-        // For now, we'll use keyboard shortcuts for tab management
-        // Full button handling can be implemented later when we understand Makepad's action API better
         ::log::debug!("Actions received: {:?}", actions.len());
+        
+        if self.ui.button(id!(new_tab_btn)).clicked(actions) {
+            self.perform_action(cx, AppAction::NewTab);
+        }
+        let tab_strip = self.ui.widget(id!(tab_strip)).widget_uid();
+        for action in actions.filter_widget_actions_cast::<crate::tab_strip::TabStripAction>(tab_strip) {
+            match action {
+                crate::tab_strip::TabStripAction::Activate(tab_id) => self.switch_to_tab(cx, tab_id),
+                crate::tab_strip::TabStripAction::Close(tab_id) => self.close_tab(cx, tab_id),
+                crate::tab_strip::TabStripAction::None => {}
+            }
+        }
     }
 }
 
@@ -245,24 +235,42 @@ impl AppMain for App {
         // Always check for PTY events at the start of each event cycle
         self.handle_pty_events(cx);
         
+        // A divider drag or keyboard resize settled: give the PTYs their new sizes
+        if self.state.pane_resize_timer.is_event(event).is_some() {
+            self.handle_window_resize(cx);
+        }
+        if self.state.tab_info_timer.is_event(event).is_some() {
+            self.state.tab_info_timer = Timer::empty();
+            if self.state.refresh_tab_info() {
+                self.ui.widget(id!(tab_strip)).redraw(cx);
+            }
+            self.update_status_bar(cx);
+        }
+        
         // Handle system events first
         match event {
             Event::WindowCloseRequested(_) => {
-                self.quit_application(cx);
+                // The platform closes the window and ends the event loop
+                ::log::info!("Quitting ModernTerm");
             }
             Event::KeyDown(key_event) => {
                 self.handle_key_down(cx, key_event);
             }
+            Event::KeyUp(key_event) => {
+                self.handle_key_up(cx, key_event);
+            }
             Event::TextInput(text_event) => {
                 self.handle_text_input(cx, text_event);
             }
-            Event::Scroll(scroll_event) => {
-                self.handle_scroll(cx, scroll_event);
-            }
             Event::WindowGeomChange(_) => {
                 // Handle window resize to adjust terminal size
                 self.handle_window_resize(cx);
             }
+            Event::AppGotFocus | Event::AppLostFocus => {
+                // Redraw so the cursor switches between solid and hollow
+                self.state.window_focused = matches!(event, Event::AppGotFocus);
+                self.ui.redraw(cx);
+            }
             // Timer events are handled in custom match_event implementation above
             _ => {}
         }
@@ -270,8 +278,8 @@ impl AppMain for App {
         // Let MatchEvent handle its events
         self.match_event(cx, event);
         
-        // Let the UI handle the event
-        self.ui.handle_event(cx, event, &mut Scope::empty());
+        // Let the UI handle the event (widgets read terminal state through the scope)
+        self.ui.handle_event(cx, event, &mut Scope::with_data(&mut self.state));
         
         // Check PTY events again after all processing - ensures immediate updates
         self.handle_pty_events(cx);
@@ -291,6 +299,9 @@ impl App {
         self.state = AppState::new();
         ::log::info!("📋 AppState initialized with {} terminals", self.state.terminals.len());
         
+        // Load user configuration before any terminal is spawned
+        self.load_config();
+        
         // Create the first tab automatically
         println!("📄 Creating initial tab - BASIC PRINTLN");
         Self::create_new_tab(&mut self.state, self.pty_signal.clone());
@@ -317,7 +328,7 @@ impl App {
     
 
 
-    pub fn new(cx: &mut Cx) -> Self {
+    pub fn new(_cx: &mut Cx) -> Self {
         // Initialize the application state
         let mut state = AppState::new();
         let pty_signal = SignalToUI::new();
@@ -329,6 +340,9 @@ impl App {
             ui: WidgetRef::default(),
             state,
             pty_signal,
+            config_signal: SignalToUI::new(),
+            config_watcher: None,
+            clipboard: ClipboardReader::default(),
         }
     }
     
@@ -337,14 +351,15 @@ impl App {
         let tab_id = state.next_tab_id;
         state.next_tab_id += 1;
         
-        // Create the terminal for this tab
-        let terminal_id = state.create_terminal(signal);
+        // Create the terminal for this tab, starting where the focused one is
+        let working_directory = state.new_terminal_directory();
+        let terminal_id = state.create_terminal_in(signal, working_directory.clone());
         
         // Create more descriptive tab title
         let shell_name = std::env::var("SHELL")
             .unwrap_or_else(|_| "/bin/bash".to_string())
             .split('/')
-            .last()
+            .next_back()
             .unwrap_or("bash")
             .to_string();
         
@@ -352,8 +367,10 @@ impl App {
         let tab = TerminalTab {
             id: tab_id,
             title: format!("{} {}", shell_name, tab_id),
-            current_directory: std::env::current_dir()
-                .ok()
+            custom_title: None,
+            bell: false,
+            current_directory: working_directory
+                .or_else(|| std::env::current_dir().ok())
                 .and_then(|p| p.to_str().map(|s| s.to_string())),
             process_name: Some(shell_name),
             input_buffer: String::new(),
@@ -370,8 +387,7 @@ impl App {
         state.tabs.insert(tab_id, tab);
         state.tab_order.push(tab_id);
         state.tab_layouts.insert(tab_id, layout);
-        state.active_tab_id = tab_id;
-        state.focused_terminal = Some(terminal_id);
+        state.activate_tab(tab_id);
         
         ::log::info!("📄 Created new tab '{}' (ID: {}) with terminal {}", 
                     tab_title, tab_id, terminal_id);
@@ -379,6 +395,109 @@ impl App {
     
     
     
+    /// Load config.toml from the XDG config dir and apply it
+    fn load_config(&mut self) {
+        let loaded = crate::config::load_config();
+        match &loaded.path {
+            Some(path) => ::log::info!("⚙️ Loaded config from {}", path.display()),
+            None => ::log::info!("⚙️ No config file found, using defaults"),
+        }
+        let mut diagnostics = loaded.diagnostics;
+        let (keymap, keymap_diagnostics) = Keymap::from_config(&loaded.config.keyboard);
+        diagnostics.extend(keymap_diagnostics);
+        let theme = Self::load_theme(&loaded.config.appearance.theme, &mut diagnostics);
+        for diagnostic in &diagnostics {
+            ::log::warn!("Config problem: {}", diagnostic);
+        }
+        self.state.status_message = Self::config_status_message(&diagnostics);
+        
+        self.state.config = loaded.config;
+        self.state.keymap = keymap;
+        self.state.theme = theme;
+        
+        // Watch the config file (even if it doesn't exist yet) for live reload
+        if let Some(path) = crate::config::config_path() {
+            self.config_signal = SignalToUI::new();
+            match crate::config::ConfigWatcher::spawn(path, self.config_signal.clone()) {
+                Ok(watcher) => self.config_watcher = Some(watcher),
+                Err(e) => ::log::error!("Failed to start config watcher: {}", e),
+            }
+        }
+    }
+    
+    /// Re-read config.toml and apply only what changed to the running terminals
+    fn reload_config(&mut self, cx: &mut Cx) {
+        let (new_config, mut diagnostics) = match crate::config::config_path() {
+            Some(path) if path.exists() => match crate::config::read_config(&path) {
+                Ok(result) => result,
+                Err(diagnostic) => {
+                    // Keep the current config while the file is broken (e.g. mid-edit)
+                    ::log::warn!("Config reload failed: {}", diagnostic);
+                    self.state.status_message = Some(format!("⚠ Config error: {}", diagnostic));
+                    self.update_status_bar(cx);
+                    return;
+                }
+            },
+            _ => (AppConfig::default(), Vec::new()),
+        };
+        
+        let (keymap, keymap_diagnostics) = Keymap::from_config(&new_config.keyboard);
+        diagnostics.extend(keymap_diagnostics);
+        // Always re-read the theme so edits to a custom theme file apply on the next save
+        let theme = Self::load_theme(&new_config.appearance.theme, &mut diagnostics);
+        for diagnostic in &diagnostics {
+            ::log::warn!("Config problem: {}", diagnostic);
+        }
+        self.state.status_message = Self::config_status_message(&diagnostics);
+        self.state.keymap = keymap;
+        self.state.theme = theme;
+        
+        let changes = crate::config::diff_config(&self.state.config, &new_config);
+        self.state.config = new_config;
+        if changes.is_empty() {
+            self.update_status_bar(cx);
+            return;
+        }
+        ::log::info!("⚙️ Config reloaded: {:?}", changes);
+        
+        if changes.font {
+            // New cell metrics: resize every PTY (the view picks up the font on redraw)
+            self.handle_window_resize(cx);
+        }
+        if changes.scrollback {
+            let lines = self.state.config.behavior.scrollback_lines;
+            for backend in self.state.terminals.values_mut() {
+                backend.process_command(crate::terminal::BackendCommand::SetScrollback(lines));
+            }
+        }
+        // Key bindings were rebuilt above; behavior flags are read from state.config on use
+        
+        self.refresh_terminal_content(cx);
+        self.update_status_bar(cx);
+        self.ui.redraw(cx);
+    }
+    
+    /// Resolve `appearance.theme`, falling back to the dark theme on errors
+    fn load_theme(name: &str, diagnostics: &mut Vec<crate::config::ConfigDiagnostic>) -> Theme {
+        match Theme::load(name) {
+            Ok(theme) => {
+                ::log::info!("🎨 Using theme '{}'", theme.name);
+                theme
+            }
+            Err(message) => {
+                diagnostics.push(crate::config::ConfigDiagnostic::new(None, format!("appearance.theme: {}", message)));
+                Theme::default()
+            }
+        }
+    }
+    
+    /// Summarize config diagnostics for the status bar
+    fn config_status_message(diagnostics: &[crate::config::ConfigDiagnostic]) -> Option<String> {
+        diagnostics.first().map(|d| {
+            format!("⚠ Config: {} ({} problem(s))", d, diagnostics.len())
+        })
+    }
+    
     /// Update status bar with current information
     fn update_status_bar(&mut self, cx: &mut Cx) {
         let tab_count = self.state.tabs.len();
@@ -387,19 +506,31 @@ impl App {
             .map(|i| i + 1)
             .unwrap_or(0);
         
-        let view_mode_text = match &self.state.view_mode {
-            ViewMode::Single => "Single Mode",
-            ViewMode::Grid { rows, cols, .. } => &format!("Grid {}x{}", rows, cols),
+        let mut view_mode_text = match &self.state.view_mode {
+            ViewMode::Single => "Single Mode".to_string(),
+            ViewMode::Grid { rows, cols, .. } => format!("Grid {}x{}", rows, cols),
         };
+        if self.state.active_tab_is_zoomed() {
+            view_mode_text.push_str(" 🔍 Zoomed");
+        }
+        if self.state.broadcast_mode {
+            // The focused terminal always types along, broadcast target or not
+            let recipients = self.state.input_recipients().len();
+            view_mode_text.push_str(&format!(" 📡 Broadcast → {} terminal(s)", recipients));
+        }
         
         let focused_terminal_info = if let Some(terminal_id) = self.state.focused_terminal {
-            format!("Terminal {}", terminal_id)
+            match self.state.terminal_directory(terminal_id) {
+                Some(dir) => format!("Terminal {} 📁 {}", terminal_id, Self::abbreviate_home(&dir)),
+                None => format!("Terminal {}", terminal_id),
+            }
         } else {
             "No Terminal".to_string()
         };
         
         let status_text = format!(
-            "Ready | {} | Tab {}/{} | {} | Ctrl+T:New Ctrl+W:Close Ctrl+Q:Quit",
+            "{} | {} | Tab {}/{} | {} | Ctrl+T:New Ctrl+W:Close Ctrl+Q:Quit",
+            self.state.status_message.as_deref().unwrap_or("Ready"),
             view_mode_text,
             current_tab_index,
             tab_count,
@@ -411,73 +542,31 @@ impl App {
     }
      
    
-    /// Extract text content from alacritty terminal grid (optimized)
-    fn extract_grid_text(content: &crate::terminal::RenderableContent) -> String {
-        use alacritty_terminal::grid::Dimensions;
-        use alacritty_terminal::index::{Line, Column};
-        
-        let grid = &content.grid;
-        let num_lines = grid.screen_lines();
-        let num_cols = grid.columns();
-        
-        // Use precise cursor position from backend renderable content
-        let cursor_line_idx: usize = content.cursor_line.min(num_lines.saturating_sub(1));
-        let cursor_col_idx: usize = content.cursor_col.min(num_cols.saturating_sub(1));
-
-        // Pre-allocate string capacity for better performance
-        let mut display_text = String::with_capacity(num_lines * num_cols);
-        
-        // Extract text from each line of the grid
-        for line_index in 0..num_lines {
-            let line = Line::from(line_index);
-            let mut line_chars: Vec<char> = Vec::with_capacity(num_cols);
-            
-            // Extract characters from each column
-            for col_index in 0..num_cols {
-                let column = Column::from(col_index);
-                let cell = &grid[line][column];
-                line_chars.push(cell.c);
-            }
-            
-            // If this is the cursor line, insert a full block cursor at the exact column.
-            if line_index == cursor_line_idx {
-                if cursor_col_idx < num_cols {
-                    line_chars[cursor_col_idx] = '\u{2588}';
-                } else if num_cols > 0 {
-                    line_chars[num_cols - 1] = '\u{2588}';
-                }
-                let line_text: String = line_chars.into_iter().collect();
-                display_text.push_str(&line_text);
-            } else {
-                // Convert to string and trim trailing spaces
-                let line_text: String = line_chars.into_iter().collect();
-                let trimmed = line_text.trim_end();
-                if !trimmed.is_empty() || line_index == 0 {
-                    display_text.push_str(trimmed);
-                }
-            }
-            
-            // Add newline except for the last line
-            if line_index < num_lines - 1 {
-                display_text.push('\n');
-            }
-        }
-        
-        // Fallback if empty
-        if display_text.trim().is_empty() {
-            display_text = "ModernTerm Terminal - Real PTY Mode\nType any command and press Enter!\n\n$ ".to_string();
+    /// Show a path under $HOME as ~/...
+    fn abbreviate_home(dir: &std::path::Path) -> String {
+        match std::env::var_os("HOME").and_then(|home| dir.strip_prefix(home).ok().map(|rest| rest.to_path_buf())) {
+            Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+            Some(rest) => format!("~/{}", rest.display()),
+            None => dir.display().to_string(),
         }
-
-        display_text
     }
-
-
-
+    
     /// Handle PTY signals using SignalToUI (non-blocking)
     fn handle_pty_events(&mut self, cx: &mut Cx) {
+        // Config file changes arrive through their own signal
+        if self.config_signal.check_and_clear() {
+            self.reload_config(cx);
+        }
+        
+        for (request, text) in self.clipboard.take_results() {
+            self.handle_clipboard_text(cx, request, text);
+        }
+        
         // Check for SignalToUI signals from PTY thread (non-blocking)
         if self.pty_signal.check_and_clear() {
             println!("🎨 SignalToUI signal received! Refreshing terminal...");
+            self.drain_terminal_events(cx);
+            self.state.schedule_tab_info_refresh(cx);
             // Signal received - refresh terminal content and redraw UI
             self.refresh_terminal_content(cx);
             cx.redraw_all();
@@ -485,6 +574,92 @@ impl App {
         }
     }
     
+    /// Deliver clipboard text read off the UI thread
+    fn handle_clipboard_text(&mut self, cx: &mut Cx, request: ClipboardRequest, text: Option<String>) {
+        match (request, text) {
+            (ClipboardRequest::Paste, Some(text)) => self.paste_text(cx, &text),
+            (ClipboardRequest::Paste, None) => {
+                ::log::warn!("No clipboard tool available for paste");
+                self.state.status_message = Some("⚠ Paste: clipboard unavailable (install wl-paste, xclip or xsel)".to_string());
+                self.update_status_bar(cx);
+            }
+            (ClipboardRequest::Osc52Load(terminal_id, format), Some(text)) => {
+                if let Some(backend) = self.state.terminals.get(&terminal_id) {
+                    backend.reply(format(&text));
+                }
+            }
+            (ClipboardRequest::Osc52Load(terminal_id, _), None) => {
+                ::log::warn!("OSC 52 clipboard read for terminal {}: no clipboard tool available", terminal_id);
+            }
+        }
+    }
+    
+    /// Act on the alacritty events each terminal queued (titles, clipboard, replies, exit)
+    fn drain_terminal_events(&mut self, cx: &mut Cx) {
+        let terminal_ids: Vec<u64> = self.state.terminals.keys().copied().collect();
+        for terminal_id in terminal_ids {
+            let Some(backend) = self.state.terminals.get(&terminal_id) else {
+                continue;
+            };
+            for event in backend.take_events() {
+                self.handle_terminal_event(cx, terminal_id, event);
+            }
+        }
+    }
+    
+    fn handle_terminal_event(&mut self, cx: &mut Cx, terminal_id: u64, event: PtyEvent) {
+        let Some(backend) = self.state.terminals.get_mut(&terminal_id) else {
+            return;
+        };
+        match event {
+            PtyEvent::Title(title) => backend.set_title(Some(title)),
+            PtyEvent::ResetTitle => backend.set_title(None),
+            // OSC 52; alacritty only passes these on when its osc52 setting allows them
+            PtyEvent::ClipboardStore(_, text) => cx.copy_to_clipboard(&text),
+            // Answered once the clipboard was read off the UI thread
+            PtyEvent::ClipboardLoad(_, format) => self.clipboard.request(ClipboardRequest::Osc52Load(terminal_id, format)),
+            // OSC 4/10/11/12 queries: answer with the color the view actually draws
+            PtyEvent::ColorRequest(index, format) => {
+                let rgb = self.state.theme.lookup(index, &backend.last_content().colors);
+                backend.reply(format(rgb));
+            }
+            // Device status / attribute reports
+            PtyEvent::PtyWrite(text) => backend.reply(text),
+            PtyEvent::TextAreaSizeRequest(format) => {
+                let size = backend.window_size();
+                backend.reply(format(size));
+            }
+            PtyEvent::CursorBlinkingChange => self.ui.widget(id!(terminal_panes)).redraw(cx),
+            PtyEvent::Bell => self.ring_bell(cx, terminal_id),
+            PtyEvent::ChildExit(code) => backend.mark_exited(Some(code)),
+            PtyEvent::Exit => {
+                backend.mark_exited(None);
+                ::log::info!("🚪 Terminal {} exited (code {:?})", terminal_id, backend.exit_code());
+                if self.state.config.behavior.close_tab_on_exit {
+                    self.close_pane(cx, terminal_id);
+                } else {
+                    // The view shows the restart prompt
+                    self.ui.widget(id!(terminal_panes)).redraw(cx);
+                }
+            }
+            PtyEvent::Wakeup | PtyEvent::MouseCursorDirty => {}
+        }
+    }
+    
+    /// Mark a background tab whose pane rang the bell
+    fn ring_bell(&mut self, cx: &mut Cx, terminal_id: u64) {
+        ::log::info!("🔔 Bell in terminal {}", terminal_id);
+        let Some(tab_id) = self.state.tab_of_terminal(terminal_id) else {
+            return;
+        };
+        if tab_id != self.state.active_tab_id {
+            if let Some(tab) = self.state.tabs.get_mut(&tab_id) {
+                tab.bell = true;
+            }
+            self.ui.widget(id!(tab_strip)).redraw(cx);
+        }
+    }
+    
     /// Check if a layout contains a specific terminal
     fn contains_terminal_in_layout(&self, layout: &PanelContent, terminal_id: u64) -> bool {
         match layout {
@@ -498,7 +673,38 @@ impl App {
     
     /// Handle text input events
     fn handle_text_input(&mut self, cx: &mut Cx, text_event: &TextInputEvent) {
-        if let Some(terminal_id) = self.state.focused_terminal {
+        // Typing (or pasting) into a tab name being edited
+        if let Some((_, name)) = &mut self.state.tab_rename {
+            let line = text_event.input.lines().next().unwrap_or("");
+            name.extend(line.chars().filter(|ch| !ch.is_control()));
+            self.ui.widget(id!(tab_strip)).redraw(cx);
+            return;
+        }
+        
+        if text_event.was_paste {
+            // Ctrl+V always makes the platform fetch the clipboard; only paste when a Paste binding asked
+            if std::mem::take(&mut self.state.paste_pending) {
+                self.paste_text(cx, &text_event.input);
+            }
+            return;
+        }
+        
+        // With kitty REPORT_ALL_KEYS_AS_ESC the key presses already carried the
+        // text; only IME commits and other non-ASCII text still go out as text
+        let ascii = text_event.input.is_ascii();
+        let recipients: Vec<u64> = self
+            .state
+            .input_recipients()
+            .into_iter()
+            .filter(|id| {
+                !(ascii && self.state.terminals.get(id).is_some_and(|backend| crate::input::keys_report_text(backend.mode())))
+            })
+            .collect();
+        if recipients.is_empty() && self.state.focused_terminal.is_some() {
+            return;
+        }
+        
+        if self.state.focused_terminal.is_some() {
             // Update the input buffer for current tab
             let active_tab_id = self.state.active_tab_id;
             
@@ -534,9 +740,7 @@ impl App {
                         // No need for simulation anymore!
                         
                         // Send to actual terminal backend too
-                        if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
-                            backend.process_command(crate::terminal::BackendCommand::Write(b"\r".to_vec()));
-                        }
+                        self.write_to_terminals(&recipients, b"\r");
                         
                         // Update display immediately after Enter
                         self.refresh_terminal_content(cx);
@@ -559,18 +763,16 @@ impl App {
                         }
                         
                         // Send to terminal backend
-                        if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
-                            let mut char_bytes = [0u8; 4];
-                            let char_str = ch.encode_utf8(&mut char_bytes);
-                            backend.process_command(crate::terminal::BackendCommand::Write(char_str.as_bytes().to_vec()));
-                        }
+                        let mut char_bytes = [0u8; 4];
+                        let char_str = ch.encode_utf8(&mut char_bytes);
+                        self.write_to_terminals(&recipients, char_str.as_bytes());
                         
                         // Reduce refresh frequency for regular typing
                         // Only refresh every few characters for performance
                         static mut CHAR_COUNT: u32 = 0;
                         unsafe {
                             CHAR_COUNT += 1;
-                            if CHAR_COUNT % 3 == 0 { // Update every 3rd character
+                            if CHAR_COUNT.is_multiple_of(3) { // Update every 3rd character
                                 self.refresh_terminal_content(cx);
                             }
                         }
@@ -587,106 +789,17 @@ impl App {
         }
     }
     
-    /// Handle scroll events for terminal history
-    fn handle_scroll(&mut self, cx: &mut Cx, scroll_event: &ScrollEvent) {
-        if let Some(terminal_id) = self.state.focused_terminal {
-            if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
-                // Convert scroll to terminal scroll delta
-                // Negative delta = scroll up (show older content)
-                // Positive delta = scroll down (show newer content)
-                let delta = -(scroll_event.scroll.y * 3.0) as i32; // Multiply for sensitivity and invert
-                
-                ::log::info!("📜 Scroll event: delta={}, terminal_id={}", delta, terminal_id);
-                
-                // Send scroll command to terminal backend
-                backend.process_command(crate::terminal::BackendCommand::Scroll(delta));
-                
-                // User scrolled: disable follow at bottom until they return
-                self.state.follow_output.insert(terminal_id, false);
-                // Update display immediately after scroll
-                self.refresh_terminal_content(cx);
-            }
-        }
-    }
-    
     /// Refresh terminal content display (optimized)
     fn refresh_terminal_content(&mut self, cx: &mut Cx) {
-        // Get the current terminal content from the backend
-        if let Some(terminal_id) = self.state.focused_terminal {
-            // Split borrows: get content first, then release backend borrow
-            let content_opt = if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
-                Some(backend.sync().clone())
-            } else { None };
-            if let Some(content) = content_opt.as_ref() {
-                // Check if we're in alternative screen mode (like top, vi, etc.)
-                let is_alt_screen = content.terminal_mode.contains(TermMode::ALT_SCREEN);
-                
-                // Extract text content from terminal grid
-                let terminal_text = Self::extract_grid_text(content);
-                
-                // Debug: Log alternative screen mode (keep this for now)
-                if is_alt_screen {
-                    println!("🔍 Alternative screen mode detected");
-                }
-                
-                // Update the terminal display Label with actual content
-                let terminal_display_label = self.ui.label(id!(terminal_display));
-                
-                // Debug: Check if label is found (simplified)
-                if terminal_display_label.is_empty() {
-                    println!("❌ Error: terminal_display label not found!");
-                }
-                
-                terminal_display_label.set_text(cx, &terminal_text);
-
-                // Force immediate and strong UI refresh
-                if is_alt_screen {
-                    // Alternative screen mode (like top, vi) needs aggressive redraw
-                    println!("🔥 ALT_SCREEN: Performing aggressive redraw");
-                    cx.redraw_all();
-                    self.ui.redraw(cx);
-                    
-                    // Force redraw of all UI components for alternative screen apps
-                    let root_view = self.ui.view(id!(root));
-                    root_view.redraw(cx);
-                    
-                    let terminal_area = self.ui.view(id!(terminal_area));
-                    terminal_area.redraw(cx);
-                    
-                    // Additional aggressive refresh
-                    terminal_display_label.redraw(cx);
-                } else {
-                    // Normal mode - standard redraw
-                    cx.redraw_all();
-                    self.ui.redraw(cx);
-                }
-
-                // Content will automatically fit using height: Fit in terminal_display
-
-                // Auto-scroll to cursor position when enabled
-                if let Some(terminal_id) = self.state.focused_terminal {
-                    let follow = self.state.follow_output.get(&terminal_id).copied().unwrap_or(true);
-                    if follow {
-                        let area_view = self.ui.view(id!(terminal_area));
-                        
-                        // Calculate cursor position in pixels
-                        let line_height = 12.0; // Font line height
-                        let cursor_y_pixels = (content.cursor_line as f64) * line_height;
-                        
-                        // Get view dimensions
-                        let view_rect = area_view.area().rect(cx);
-                        let view_height = view_rect.size.y;
-                        
-                        // Only scroll if cursor is beyond visible area
-                        if cursor_y_pixels > view_height {
-                            // Scroll so cursor is near bottom of visible area
-                            let target_scroll_y = cursor_y_pixels - view_height + (line_height * 2.0);
-                            area_view.set_scroll_pos(cx, DVec2 { x: 0.0, y: target_scroll_y.max(0.0) });
-                        }
-                    }
-                }
+        // Snapshot every visible pane's grid; the views draw from last_content()
+        for terminal_id in self.state.visible_terminals() {
+            if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
+                backend.sync();
             }
         }
+        
+        self.ui.widget(id!(terminal_panes)).redraw(cx);
+        self.update_status_bar(cx);
     }
     
     /// Handle window resize to adjust terminal size
@@ -699,170 +812,436 @@ impl App {
         
         ::log::info!("📐 Terminal area size: {}x{}", rect.size.x, rect.size.y);
         
-        // Calculate available space (minus padding)
-        let padding = 24.0f64; // 12px padding on each side
-        let available_width = (rect.size.x - padding).max(100.0f64);
-        let available_height = (rect.size.y - padding).max(100.0f64);
-        
-        // Font metrics based on the current settings (10px font size)
-        let font_width = 6.0f64; // Approximate monospace character width for 10px
-        let font_height = 12.0f64; // 10px * 1.2 line spacing
-        
-        // Calculate new terminal dimensions
-        let new_cols = (available_width / font_width).floor() as u16;
-        let new_rows = (available_height / font_height).floor() as u16;
+        // Before the first draw the area is empty; fall back to a sane size
+        let rect = if rect.size.x > 0.0 && rect.size.y > 0.0 {
+            rect
+        } else {
+            Rect { pos: rect.pos, size: dvec2(800.0, 600.0) }
+        };
         
-        ::log::info!("🎯 Calculated terminal size: {}x{} (was default 80x24)", new_cols, new_rows);
+        // Font metrics derived from the configured font size
+        let cell_size = self.state.config.appearance.cell_size();
         
-        // Resize all active terminals
-        for (&terminal_id, backend) in self.state.terminals.iter_mut() {
-            let new_size = crate::types::Size {
-                width: available_width as f32,
-                height: available_height as f32,
-            };
-            
-            let font_size = crate::types::Size {
-                width: font_width as f32,
-                height: font_height as f32,
+        // Tabs share the terminal area (or get a grid cell); each visible pane gets its
+        // own slice of that (panes hidden behind a zoomed one keep their size until unzoom)
+        for (tab_id, tab_area) in crate::layout::tab_areas(&self.state.view_mode, &self.state.tab_order, rect) {
+            let Some(layout) = self.state.visible_layout(tab_id) else {
+                continue;
             };
-            
-            backend.process_command(crate::terminal::BackendCommand::Resize(new_size, font_size));
-            ::log::info!("📏 Resized terminal {} to {}x{}", terminal_id, new_cols, new_rows);
+            for (terminal_id, pane_rect) in crate::layout::pane_rects(&layout, tab_area) {
+                let Some(backend) = self.state.terminals.get_mut(&terminal_id) else {
+                    continue;
+                };
+                let grid_size = crate::layout::grid_size(pane_rect, cell_size);
+                backend.process_command(crate::terminal::BackendCommand::Resize(grid_size, cell_size));
+                ::log::info!("📏 Resized terminal {} to {}x{}px", terminal_id, grid_size.width, grid_size.height);
+            }
         }
         
         // Update display after resize
         self.refresh_terminal_content(cx);
     }
     
+    /// Edit the name of the tab being renamed: Enter keeps it, Escape cancels
+    fn handle_rename_key(&mut self, cx: &mut Cx, key_event: &KeyEvent) {
+        let Some((tab_id, name)) = &mut self.state.tab_rename else {
+            return;
+        };
+        match key_event.key_code {
+            KeyCode::ReturnKey => {
+                let tab_id = *tab_id;
+                let name = name.trim().to_string();
+                if let Some(tab) = self.state.tabs.get_mut(&tab_id) {
+                    // An empty name hands the title back to OSC/process tracking
+                    tab.custom_title = (!name.is_empty()).then_some(name);
+                    ::log::info!("✏️ Tab {} title: {}", tab_id, tab.display_title());
+                }
+                self.state.tab_rename = None;
+            }
+            KeyCode::Escape => self.state.tab_rename = None,
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            // Characters arrive as TextInput (Ctrl+V pastes that way too)
+            _ => {}
+        }
+        self.ui.widget(id!(tab_strip)).redraw(cx);
+    }
+    
+    /// Report key releases to programs using the kitty keyboard protocol
+    fn handle_key_up(&mut self, cx: &mut Cx, key_event: &KeyEvent) {
+        if self.state.tab_rename.is_some() {
+            return;
+        }
+        if !matches!(self.state.keymap.lookup(key_event), Some(AppAction::Passthrough) | None) {
+            return;
+        }
+        if self.write_key(key_event, KeyEventType::Release) {
+            self.refresh_terminal_content(cx);
+        }
+    }
+    
     /// Handle keyboard input
     fn handle_key_down(&mut self, cx: &mut Cx, key_event: &KeyEvent) {
         let modifiers = &key_event.modifiers;
         
         ::log::info!("Key down event: {:?} with modifiers: {:?}", key_event.key_code, modifiers);
+        self.state.paste_pending = false;
+        
+        if self.state.tab_rename.is_some() {
+            self.handle_rename_key(cx, key_event);
+            return;
+        }
         
         // Handle application shortcuts (based on tterm's InputHandler)
-        match key_event.key_code {
-            KeyCode::ReturnKey => {
-                // Handle Enter key explicitly
-                ::log::info!("⏎ Enter key detected in KeyDown!");
-                if let Some(terminal_id) = self.state.focused_terminal {
-                    let active_tab_id = self.state.active_tab_id;
-                    
-                                         let _command = if let Some(tab) = self.state.tabs.get_mut(&active_tab_id) {
-                        let cmd = tab.input_buffer.trim().to_string();
-                        if !cmd.is_empty() {
-                            // Add to command history
-                            tab.command_history.push(cmd.clone());
-                            // Clear input buffer
-                            tab.input_buffer.clear();
-                            Some(cmd)
-                        } else {
-                            // Even for empty commands, add a blank line to simulate Enter
-                            tab.output_lines.push("".to_string());
-                            tab.input_buffer.clear();
-                            None
+        match self.state.keymap.lookup(key_event) {
+            Some(AppAction::Paste) if Self::platform_pastes(key_event) => {
+                // The platform fetches the clipboard itself and sends it as a paste TextInput
+                self.state.paste_pending = true;
+            }
+            Some(AppAction::Passthrough) | None => {
+                // Enter in an exited pane starts its shell again
+                if key_event.key_code == KeyCode::ReturnKey {
+                    if let Some(terminal_id) = self.state.focused_terminal {
+                        if self.state.terminals.get(&terminal_id).is_some_and(|backend| backend.has_exited()) {
+                            self.restart_terminal(cx, terminal_id);
+                            return;
                         }
-                    } else {
-                        None
-                    };
-                    
-                    // Note: Real PTY will handle command execution
-                    // No need for simulation anymore!
-                    
-                    // Send to actual terminal backend too
-                    if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
-                        backend.process_command(crate::terminal::BackendCommand::Write(b"\r".to_vec()));
                     }
-                    
-                    // Update display immediately after Enter
-                    self.refresh_terminal_content(cx);
+                }
+                // Forward other keys to the focused terminal
+                self.forward_key_to_terminal(cx, key_event);
+                // Typing implies user wants to follow the latest output again
+                for terminal_id in self.state.input_recipients() {
+                    self.state.follow_output.insert(terminal_id, true);
                 }
             }
-            KeyCode::KeyT if modifiers.control => {
-                // Ctrl+T: New tab
+            Some(action) => self.perform_action(cx, action),
+        }
+    }
+    
+    /// Run an application action bound in the keymap
+    fn perform_action(&mut self, cx: &mut Cx, action: AppAction) {
+        ::log::info!("Keymap action: {:?}", action);
+        match action {
+            AppAction::NewTab => {
                 Self::create_new_tab(&mut self.state, self.pty_signal.clone());
-                self.refresh_terminal_content(cx);
-                ::log::info!("New tab created via Ctrl+T");
-            }
-            KeyCode::KeyW if modifiers.control => {
-                // Ctrl+W: Close tab
-                self.close_current_tab(cx);
-            }
-            KeyCode::KeyS if modifiers.control => {
-                // Ctrl+S: Toggle grid view
-                self.toggle_grid_view(cx);
-            }
-            KeyCode::KeyQ if modifiers.control => {
-                // Ctrl+Q: Quit application
-                self.quit_application(cx);
-            }
-            KeyCode::KeyR if modifiers.control => {
-                // Ctrl+R: Refresh terminal display
-                self.refresh_terminal_content(cx);
-                ::log::info!("Terminal display refreshed via Ctrl+R");
+                self.update_grid_dimensions();
+                // Size the new PTY (and, in grid view, every reshuffled cell)
+                self.handle_window_resize(cx);
+                self.ui.redraw(cx);
             }
-            KeyCode::KeyL if modifiers.control && modifiers.shift => {
-                // Ctrl+Shift+L: Clear terminal (send clear command)
+            AppAction::CloseTab => self.close_current_tab(cx),
+            AppAction::SplitVertical => self.split_focused_pane(cx, SplitDirection::Vertical),
+            AppAction::SplitHorizontal => self.split_focused_pane(cx, SplitDirection::Horizontal),
+            AppAction::ToggleGrid => self.toggle_grid_view(cx),
+            AppAction::Quit => self.quit_application(cx),
+            AppAction::RefreshDisplay => self.refresh_terminal_content(cx),
+            AppAction::ClearScreen => {
+                // Clear terminal (send clear command)
                 if let Some(terminal_id) = self.state.focused_terminal {
                     if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
                         backend.process_command(crate::terminal::BackendCommand::Write(b"clear\r".to_vec()));
                         self.refresh_terminal_content(cx);
-                        ::log::info!("Sent clear command via Ctrl+Shift+L");
                     }
                 }
             }
-            KeyCode::PageUp => {
-                // Page Up: Scroll up in terminal history
-                if let Some(terminal_id) = self.state.focused_terminal {
-                    if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
-                        let delta = if modifiers.shift { -10 } else { -5 }; // Shift+PageUp scrolls more
-                        backend.process_command(crate::terminal::BackendCommand::Scroll(delta));
-                        self.refresh_terminal_content(cx);
-                        ::log::info!("Page Up scroll: delta={}", delta);
-                    }
+            AppAction::ScrollUp => self.scroll_focused_terminal(cx, -5),
+            AppAction::ScrollDown => self.scroll_focused_terminal(cx, 5),
+            AppAction::ScrollPageUp => self.scroll_focused_terminal(cx, -10),
+            AppAction::ScrollPageDown => self.scroll_focused_terminal(cx, 10),
+            AppAction::Copy => self.copy_selection(cx),
+            AppAction::Paste => self.clipboard.request(ClipboardRequest::Paste),
+            AppAction::SwitchToTab(number) => self.switch_to_tab_by_number(cx, number),
+            AppAction::NextTab => self.cycle_tab(cx, 1),
+            AppAction::PreviousTab => self.cycle_tab(cx, -1),
+            AppAction::LastTab => self.switch_to_tab_by_number(cx, self.state.tab_order.len()),
+            AppAction::LastUsedTab => {
+                if let Some(tab_id) = self.state.last_used_tab() {
+                    self.switch_to_tab(cx, tab_id);
                 }
             }
-            KeyCode::PageDown => {
-                // Page Down: Scroll down in terminal history
+            AppAction::MoveTabLeft => self.move_active_tab(cx, -1),
+            AppAction::MoveTabRight => self.move_active_tab(cx, 1),
+            AppAction::ReopenClosedTab => self.reopen_closed_tab(cx),
+            AppAction::RenameTab => {
+                let tab_id = self.state.active_tab_id;
+                self.state.start_tab_rename(tab_id);
+                self.ui.widget(id!(tab_strip)).redraw(cx);
+            }
+            AppAction::ResizePane(direction) => self.resize_focused_pane(cx, direction),
+            AppAction::ToggleZoom => self.toggle_zoom(cx),
+            AppAction::ToggleBroadcast => self.toggle_broadcast(cx),
+            AppAction::GrowGridCell => self.resize_grid_cell(cx, 0.05),
+            AppAction::ShrinkGridCell => self.resize_grid_cell(cx, -0.05),
+            AppAction::ClosePane => {
                 if let Some(terminal_id) = self.state.focused_terminal {
-                    if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
-                        let delta = if modifiers.shift { 10 } else { 5 }; // Shift+PageDown scrolls more
-                        backend.process_command(crate::terminal::BackendCommand::Scroll(delta));
-                        self.refresh_terminal_content(cx);
-                        ::log::info!("Page Down scroll: delta={}", delta);
-                    }
+                    self.close_pane(cx, terminal_id);
                 }
             }
-            code if modifiers.control => {
-                // Ctrl+1-9: Switch to tab by number
-                if let Some(tab_number) = code.to_digit() {
-                    self.switch_to_tab_by_number(cx, tab_number as usize);
+            AppAction::FocusPane(direction) => self.focus_pane_in_direction(cx, direction),
+            AppAction::FocusPreviousPane => {
+                // Only toggle within the active tab; the previous pane may have been closed
+                let previous = self.state.previous_focused_terminal.filter(|id| {
+                    self.state.visible_layout(self.state.active_tab_id)
+                        .is_some_and(|layout| self.contains_terminal_in_layout(&layout, *id))
+                });
+                if let Some(previous) = previous {
+                    self.state.focus_terminal(previous);
+                    self.refresh_terminal_content(cx);
+                    self.ui.redraw(cx);
                 }
             }
-            _ => {
-                // Forward other keys to the focused terminal
-                self.forward_key_to_terminal(cx, key_event);
-                // Typing implies user wants to follow the latest output again
-                if let Some(terminal_id) = self.state.focused_terminal {
-                    self.state.follow_output.insert(terminal_id, true);
+            AppAction::EqualizeSplits => {
+                if let Some(layout) = self.state.tab_layouts.get_mut(&self.state.active_tab_id) {
+                    layout.equalize();
+                    self.handle_window_resize(cx);
+                    self.ui.redraw(cx);
                 }
             }
+            AppAction::Passthrough => {}
+        }
+    }
+    
+    /// Split the focused pane in two and focus the new terminal
+    fn split_focused_pane(&mut self, cx: &mut Cx, direction: SplitDirection) {
+        let Some(focused) = self.state.focused_terminal else {
+            return;
+        };
+        // Splitting a zoomed pane brings the rest of the layout back
+        self.state.zoomed_panes.remove(&self.state.active_tab_id);
+        let working_directory = self.state.new_terminal_directory();
+        let new_terminal = self.state.create_terminal_in(self.pty_signal.clone(), working_directory);
+        let split = self.state.tab_layouts
+            .get_mut(&self.state.active_tab_id)
+            .is_some_and(|layout| layout.split_terminal(focused, direction, new_terminal));
+        if !split {
+            // Focus was stale (not in the active tab); don't leak the new backend
+            self.state.terminals.remove(&new_terminal);
+            self.state.korean_input_states.remove(&new_terminal);
+            return;
+        }
+        self.state.focus_terminal(new_terminal);
+        ::log::info!("🪟 Split terminal {} {:?} → new terminal {}", focused, direction, new_terminal);
+        
+        // Both halves need their new sizes before the next draw
+        self.handle_window_resize(cx);
+        self.ui.redraw(cx);
+    }
+    
+    /// Close one pane of its tab; its sibling takes over the parent split's space
+    fn close_pane(&mut self, cx: &mut Cx, terminal_id: u64) {
+        let sibling = match self.state.detach_pane(terminal_id) {
+            Some(PaneRemoval::Collapsed { sibling, .. }) => sibling,
+            Some(PaneRemoval::LastInTab(tab_id)) => {
+                self.close_tab(cx, tab_id);
+                return;
+            }
+            None => return,
+        };
+        
+        self.state.remove_terminal(terminal_id);
+        if self.state.focused_terminal == Some(terminal_id) {
+            // Don't let "previous pane" point back at the pane that is gone
+            self.state.focused_terminal = None;
+            self.state.focus_terminal(sibling);
+        }
+        ::log::info!("🗑 Closed pane {}, focus on terminal {}", terminal_id, sibling);
+        
+        // The promoted sibling grows into the freed space
+        self.handle_window_resize(cx);
+        self.ui.redraw(cx);
+    }
+    
+    /// Start a fresh shell in a pane whose shell exited, with the settings it had
+    fn restart_terminal(&mut self, cx: &mut Cx, terminal_id: u64) {
+        let Some(settings) = self.state.terminals.get(&terminal_id).map(|backend| backend.settings().clone()) else {
+            return;
+        };
+        match crate::terminal::TerminalBackend::new(terminal_id, self.pty_signal.clone(), settings) {
+            Ok(backend) => {
+                self.state.terminals.insert(terminal_id, backend);
+                self.state.follow_output.insert(terminal_id, true);
+                ::log::info!("🔄 Restarted terminal {}", terminal_id);
+            }
+            Err(e) => {
+                ::log::error!("Failed to restart terminal {}: {}", terminal_id, e);
+                self.state.status_message = Some(format!("⚠ Restart failed: {}", e));
+            }
+        }
+        // The new PTY starts at the default size
+        self.handle_window_resize(cx);
+        self.ui.redraw(cx);
+    }
+    
+    /// Zoom the focused pane to the whole tab area, or restore the split layout
+    fn toggle_zoom(&mut self, cx: &mut Cx) {
+        let tab_id = self.state.active_tab_id;
+        if self.state.zoomed_panes.remove(&tab_id).is_some() {
+            ::log::info!("🔍 Unzoomed tab {}", tab_id);
+        } else if let Some(focused) = self.state.focused_terminal {
+            if !self.state.active_tab_is_split() {
+                return;
+            }
+            self.state.zoomed_panes.insert(tab_id, focused);
+            ::log::info!("🔍 Zoomed terminal {} in tab {}", focused, tab_id);
+        }
+        // Zoomed pane takes the full area; on unzoom every pane gets its split size back
+        self.handle_window_resize(cx);
+        self.ui.redraw(cx);
+    }
+    
+    /// Turn input broadcasting on or off (Ctrl+click picks the targets while it is on)
+    fn toggle_broadcast(&mut self, cx: &mut Cx) {
+        self.state.broadcast_mode = !self.state.broadcast_mode;
+        if self.state.broadcast_mode {
+            ::log::info!("📡 Broadcast on: {} terminal(s)", self.state.input_recipients().len());
+            // Drop an earlier "Broadcast off" notice
+            if self.state.status_message.as_deref().is_some_and(|message| message.starts_with("📡")) {
+                self.state.status_message = None;
+            }
+        } else {
+            // Start from "all terminals" again next time
+            self.state.selected_terminals.clear();
+            ::log::info!("📡 Broadcast off");
+        }
+        self.update_status_bar(cx);
+        self.ui.redraw(cx);
+    }
+    
+    /// Move focus to the nearest pane in a direction (Alt+Arrow)
+    fn focus_pane_in_direction(&mut self, cx: &mut Cx, direction: PaneDirection) {
+        let Some(focused) = self.state.focused_terminal else {
+            return;
+        };
+        // In grid view this crosses into neighbouring tabs' cells
+        let area = self.ui.widget(id!(terminal_panes)).area().rect(cx);
+        let panes = self.state.visible_pane_rects(area);
+        if let Some(target) = crate::layout::neighbor(&panes, focused, direction) {
+            self.state.focus_terminal(target);
+            ::log::info!("🎯 Focus {:?} → terminal {}", direction, target);
+            self.refresh_terminal_content(cx);
+            self.ui.redraw(cx);
+        }
+    }
+    
+    /// Nudge the divider next to the focused pane (Ctrl+Alt+Arrow)
+    fn resize_focused_pane(&mut self, cx: &mut Cx, direction: PaneDirection) {
+        let Some(focused) = self.state.focused_terminal else {
+            return;
+        };
+        let delta = match direction {
+            PaneDirection::Left | PaneDirection::Up => -0.05,
+            PaneDirection::Right | PaneDirection::Down => 0.05,
+        };
+        let moved = self.state.tab_layouts
+            .get_mut(&self.state.active_tab_id)
+            .is_some_and(|layout| layout.adjust_ratio_around(focused, direction.split_direction(), delta));
+        if moved {
+            // Key repeat would otherwise resize the PTYs on every step
+            self.state.schedule_pane_resize(cx);
+            self.ui.redraw(cx);
+        }
+    }
+    
+    /// Copy the focused terminal's selection to the system clipboard
+    fn copy_selection(&mut self, cx: &mut Cx) {
+        let text = self.state.focused_terminal
+            .and_then(|id| self.state.terminals.get(&id))
+            .and_then(|backend| backend.selection_text());
+        if let Some(text) = text {
+            cx.copy_to_clipboard(&text);
+            ::log::info!("📋 Copied {} chars to clipboard", text.chars().count());
+        }
+    }
+    
+    /// Whether makepad reads the clipboard on its own for this chord (Ctrl/Cmd+V on desktop)
+    fn platform_pastes(key_event: &KeyEvent) -> bool {
+        key_event.key_code == KeyCode::KeyV && (key_event.modifiers.control || key_event.modifiers.logo)
+    }
+    
+    /// Send clipboard text to the focused terminal (and the broadcast targets),
+    /// bracketed for each one that enabled it
+    fn paste_text(&mut self, cx: &mut Cx, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let recipients: Vec<(u64, bool)> = self
+            .state
+            .input_recipients()
+            .into_iter()
+            .filter_map(|terminal_id| {
+                let backend = self.state.terminals.get_mut(&terminal_id)?;
+                let bracketed = backend.sync().terminal_mode.contains(alacritty_terminal::term::TermMode::BRACKETED_PASTE);
+                Some((terminal_id, bracketed))
+            })
+            .collect();
+        if recipients.is_empty() {
+            return;
+        }
+        
+        // Without bracketed paste every line break runs a command; make the user paste twice
+        let line_breaks = crate::clipboard::line_breaks(text);
+        if recipients.iter().any(|(_, bracketed)| !bracketed)
+            && line_breaks > 0
+            && self.state.config.behavior.warn_multiline_paste
+            && self.state.paste_confirm.as_deref() != Some(text)
+        {
+            self.state.paste_confirm = Some(text.to_string());
+            self.state.status_message = Some(format!(
+                "⚠ Paste has {} line break(s) and bracketed paste is off — paste again to send it",
+                line_breaks
+            ));
+            self.update_status_bar(cx);
+            return;
+        }
+        if self.state.paste_confirm.take().is_some() {
+            self.state.status_message = None;
+        }
+        
+        for &(terminal_id, bracketed) in &recipients {
+            if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
+                backend.process_command(crate::terminal::BackendCommand::Write(crate::clipboard::paste_bytes(text, bracketed)));
+            }
+            self.state.follow_output.insert(terminal_id, true);
+        }
+        ::log::info!("📋 Pasted {} bytes into {} terminal(s)", text.len(), recipients.len());
+        self.refresh_terminal_content(cx);
+    }
+    
+    /// Scroll the focused terminal's history by a number of lines
+    fn scroll_focused_terminal(&mut self, cx: &mut Cx, delta: i32) {
+        if let Some(terminal_id) = self.state.focused_terminal {
+            if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
+                backend.process_command(crate::terminal::BackendCommand::Scroll(delta));
+                self.state.follow_output.insert(terminal_id, false);
+                self.refresh_terminal_content(cx);
+            }
         }
     }
     
     /// Close the current tab
     fn close_current_tab(&mut self, cx: &mut Cx) {
-        let active_tab_id = self.state.active_tab_id;
-        
+        self.close_tab(cx, self.state.active_tab_id);
+    }
+    
+    /// Close a tab and its terminals; the neighbour becomes active if it was the active one
+    fn close_tab(&mut self, cx: &mut Cx, tab_id: u64) {
+        if !self.state.tabs.contains_key(&tab_id) {
+            return;
+        }
         if self.state.tabs.len() <= 1 {
             // Last tab - quit application
             self.quit_application(cx);
             return;
         }
         
+        self.remember_closed_tab(tab_id);
+        
         // Remove tab from order
-        if let Some(pos) = self.state.tab_order.iter().position(|&id| id == active_tab_id) {
+        if let Some(pos) = self.state.tab_order.iter().position(|&id| id == tab_id) {
             self.state.tab_order.remove(pos);
+            self.state.tab_history.retain(|&id| id != tab_id);
             
             // Switch to next tab (or previous if this was the last)
             let new_active_index = if pos >= self.state.tab_order.len() {
@@ -871,24 +1250,95 @@ impl App {
                 pos
             };
             
-            if let Some(&new_active_id) = self.state.tab_order.get(new_active_index) {
-                self.state.active_tab_id = new_active_id;
-                
-                // Set focus to first terminal in the new active tab
-                if let Some(layout) = self.state.tab_layouts.get(&new_active_id) {
-                    self.state.focused_terminal = self.state.find_first_terminal_in_layout(layout);
+            if tab_id == self.state.active_tab_id {
+                if let Some(&new_active_id) = self.state.tab_order.get(new_active_index) {
+                    self.state.activate_tab(new_active_id);
                 }
             }
         }
         
         // Clean up the closed tab
-        self.state.tabs.remove(&active_tab_id);
-        if let Some(layout) = self.state.tab_layouts.remove(&active_tab_id) {
+        self.state.tabs.remove(&tab_id);
+        self.state.zoomed_panes.remove(&tab_id);
+        if self.state.tab_rename.as_ref().is_some_and(|(renaming, _)| *renaming == tab_id) {
+            self.state.tab_rename = None;
+        }
+        if let Some(layout) = self.state.tab_layouts.remove(&tab_id) {
             self.cleanup_terminals_in_layout(&layout);
         }
         
+        if self.update_grid_dimensions() {
+            self.handle_window_resize(cx);
+        }
+        self.update_status_bar(cx);
         self.ui.redraw(cx);
-        ::log::info!("Closed tab {}", active_tab_id);
+        ::log::info!("Closed tab {}", tab_id);
+    }
+    
+    /// Keep a closing tab's title, position, layout and directories for reopening
+    fn remember_closed_tab(&mut self, tab_id: u64) {
+        let (Some(tab), Some(layout)) = (self.state.tabs.get(&tab_id), self.state.tab_layouts.get(&tab_id)) else {
+            return;
+        };
+        let directories = layout
+            .terminal_ids()
+            .into_iter()
+            .filter_map(|terminal_id| Some((terminal_id, self.state.terminal_directory(terminal_id)?)))
+            .collect();
+        let closed = ClosedTab {
+            title: tab.title.clone(),
+            custom_title: tab.custom_title.clone(),
+            position: self.state.tab_order.iter().position(|&id| id == tab_id).unwrap_or(self.state.tab_order.len()),
+            layout: layout.clone(),
+            directories,
+        };
+        self.state.closed_tabs.push(closed);
+        if self.state.closed_tabs.len() > MAX_CLOSED_TABS {
+            self.state.closed_tabs.remove(0);
+        }
+    }
+    
+    /// Bring back the most recently closed tab: same title, place, splits and directories
+    fn reopen_closed_tab(&mut self, cx: &mut Cx) {
+        let Some(closed) = self.state.closed_tabs.pop() else {
+            ::log::info!("No closed tab to reopen");
+            return;
+        };
+        let tab_id = self.state.next_tab_id;
+        self.state.next_tab_id += 1;
+        
+        // One fresh shell per old pane, started where the old one was
+        let state = &mut self.state;
+        let signal = self.pty_signal.clone();
+        let layout = closed.layout.map_terminals(&mut |old_id| {
+            state.create_terminal_in(signal.clone(), closed.directories.get(&old_id).cloned())
+        });
+        let current_directory = closed
+            .directories
+            .get(&closed.layout.first_terminal())
+            .and_then(|dir| dir.to_str().map(|s| s.to_string()));
+        
+        let tab = TerminalTab {
+            id: tab_id,
+            title: closed.title.clone(),
+            custom_title: closed.custom_title.clone(),
+            bell: false,
+            current_directory,
+            process_name: None,
+            input_buffer: String::new(),
+            command_history: Vec::new(),
+            output_lines: Vec::new(),
+        };
+        self.state.tabs.insert(tab_id, tab);
+        self.state.tab_layouts.insert(tab_id, layout);
+        let position = closed.position.min(self.state.tab_order.len());
+        self.state.tab_order.insert(position, tab_id);
+        self.state.activate_tab(tab_id);
+        
+        self.update_grid_dimensions();
+        self.handle_window_resize(cx);
+        self.ui.redraw(cx);
+        ::log::info!("♻️ Reopened tab '{}' as tab {}", closed.title, tab_id);
     }
     
     /// Toggle between single and grid view
@@ -900,12 +1350,7 @@ impl App {
                     let tab_count = self.state.tabs.len();
                     let (rows, cols) = Self::calculate_optimal_grid_size(tab_count);
                     
-                    self.state.view_mode = ViewMode::Grid {
-                        rows,
-                        cols,
-                        col_ratios: vec![1.0 / cols as f32; cols],
-                        row_ratios: vec![1.0 / rows as f32; rows],
-                    };
+                    self.state.view_mode = self.state.grid_view(rows, cols);
                     
                     ::log::info!("Switched to grid view ({}x{})", rows, cols);
                 } else {
@@ -914,14 +1359,52 @@ impl App {
             }
             ViewMode::Grid { .. } => {
                 // Switch to single view
-                self.state.view_mode = ViewMode::Single;
+                self.state.set_single_view();
                 ::log::info!("Switched to single view");
             }
         }
         
+        // Every tab changes between the full area and its grid cell
+        self.handle_window_resize(cx);
         self.ui.redraw(cx);
     }
     
+    /// Grow or shrink the active tab's grid cell (its column and its row)
+    fn resize_grid_cell(&mut self, cx: &mut Cx, delta: f32) {
+        let tab_count = self.state.tab_order.len();
+        let Some(index) = self.state.tab_order.iter().position(|&id| id == self.state.active_tab_id) else {
+            return;
+        };
+        let ViewMode::Grid { rows, cols, col_ratios, row_ratios } = &mut self.state.view_mode else {
+            return;
+        };
+        if crate::layout::resize_grid_cell(tab_count, index, *rows, *cols, col_ratios, row_ratios, delta) {
+            self.state.schedule_pane_resize(cx);
+            self.ui.redraw(cx);
+        }
+    }
+    
+    /// Keep the grid big enough for every tab after tabs were opened or closed.
+    ///
+    /// Returns true in grid view, where the tabs' cells have moved.
+    fn update_grid_dimensions(&mut self) -> bool {
+        let tab_count = self.state.tab_order.len();
+        let ViewMode::Grid { rows, cols, .. } = &self.state.view_mode else {
+            return false;
+        };
+        if tab_count <= 1 {
+            self.state.set_single_view();
+            return true;
+        }
+        let (new_rows, new_cols) = Self::calculate_optimal_grid_size(tab_count);
+        if (new_rows, new_cols) != (*rows, *cols) {
+            self.state.save_grid_ratios();
+            self.state.view_mode = self.state.grid_view(new_rows, new_cols);
+            ::log::info!("Grid resized to {}x{} for {} tabs", new_rows, new_cols, tab_count);
+        }
+        true
+    }
+    
     /// Calculate optimal grid size for given number of tabs
     fn calculate_optimal_grid_size(tab_count: usize) -> (usize, usize) {
         match tab_count {
@@ -944,24 +1427,53 @@ impl App {
     fn switch_to_tab_by_number(&mut self, cx: &mut Cx, number: usize) {
         if number > 0 && number <= self.state.tab_order.len() {
             let tab_id = self.state.tab_order[number - 1];
-            self.state.active_tab_id = tab_id;
-            
-            // Set focus to first terminal in the tab
-            if let Some(layout) = self.state.tab_layouts.get(&tab_id) {
-                self.state.focused_terminal = self.state.find_first_terminal_in_layout(layout);
-            }
-            
-            self.ui.redraw(cx);
+            self.switch_to_tab(cx, tab_id);
             ::log::info!("Switched to tab {} (ID: {})", number, tab_id);
         }
     }
     
+    /// Activate a tab and focus its pane
+    fn switch_to_tab(&mut self, cx: &mut Cx, tab_id: u64) {
+        self.state.activate_tab(tab_id);
+        self.update_status_bar(cx);
+        self.ui.redraw(cx);
+    }
+    
+    /// Activate the tab `step` places away in tab order, wrapping around
+    fn cycle_tab(&mut self, cx: &mut Cx, step: isize) {
+        let count = self.state.tab_order.len();
+        let Some(index) = self.state.tab_order.iter().position(|&id| id == self.state.active_tab_id) else {
+            return;
+        };
+        if count > 1 {
+            let next = (index as isize + step).rem_euclid(count as isize) as usize;
+            self.switch_to_tab(cx, self.state.tab_order[next]);
+        }
+    }
+    
+    /// Move the active tab `step` places in tab order (stops at either end)
+    fn move_active_tab(&mut self, cx: &mut Cx, step: isize) {
+        let Some(index) = self.state.tab_order.iter().position(|&id| id == self.state.active_tab_id) else {
+            return;
+        };
+        let target = index as isize + step;
+        if target < 0 || target as usize >= self.state.tab_order.len() {
+            return;
+        }
+        self.state.tab_order.swap(index, target as usize);
+        // Grid cells follow tab order, so both tabs change places on screen
+        if matches!(self.state.view_mode, ViewMode::Grid { .. }) {
+            self.handle_window_resize(cx);
+        }
+        self.update_status_bar(cx);
+        self.ui.redraw(cx);
+    }
+    
     /// Cleanup terminals in a layout recursively
     fn cleanup_terminals_in_layout(&mut self, layout: &PanelContent) {
         match layout {
             PanelContent::Terminal(terminal_id) => {
-                self.state.terminals.remove(terminal_id);
-                self.state.korean_input_states.remove(terminal_id);
+                self.state.remove_terminal(*terminal_id);
                 ::log::debug!("Cleaned up terminal {}", terminal_id);
             }
             PanelContent::Split { first, second, .. } => {
@@ -971,100 +1483,53 @@ impl App {
         }
     }
     
-    /// Forward key events to the focused terminal
+    /// Forward key events to the focused terminal (and the broadcast targets)
     fn forward_key_to_terminal(&mut self, cx: &mut Cx, key_event: &KeyEvent) {
-        if let Some(terminal_id) = self.state.focused_terminal {
-            // First get the bytes to send
-            if let Some(bytes) = self.key_to_bytes(key_event) {
-                // Then get mutable reference to backend
-                if let Some(backend) = self.state.terminals.get_mut(&terminal_id) {
-                    backend.process_command(crate::terminal::BackendCommand::Write(bytes));
-                    ::log::debug!("Forwarded key to terminal {}: {:?}", terminal_id, key_event.key_code);
-                    
-                    // Update display after sending input
-                    self.refresh_terminal_content(cx);
-                }
-            }
+        if self.write_key(key_event, Self::key_event_type(key_event)) {
+            ::log::debug!("Forwarded key to terminals: {:?}", key_event.key_code);
+            // Update display after sending input
+            self.refresh_terminal_content(cx);
         }
     }
     
-    /// Convert key events to bytes for terminal input (based on tterm)
-    fn key_to_bytes(&self, key_event: &KeyEvent) -> Option<Vec<u8>> {
-        let modifiers = &key_event.modifiers;
-        
-        match key_event.key_code {
-            // Special keys that need specific handling
-            // Note: Return/Enter is typically handled by TextInput event in Makepad
-            KeyCode::Tab => Some(b"\t".to_vec()),
-            KeyCode::Escape => Some(b"\x1b".to_vec()),
-            KeyCode::Backspace => Some(b"\x7f".to_vec()),
-            KeyCode::Delete => Some(b"\x1b[3~".to_vec()),
-            
-            // Arrow keys
-            KeyCode::ArrowUp => Some(b"\x1b[A".to_vec()),
-            KeyCode::ArrowDown => Some(b"\x1b[B".to_vec()),
-            KeyCode::ArrowRight => Some(b"\x1b[C".to_vec()),
-            KeyCode::ArrowLeft => Some(b"\x1b[D".to_vec()),
-            
-            // Home/End
-            KeyCode::Home => Some(b"\x1b[H".to_vec()),
-            KeyCode::End => Some(b"\x1b[F".to_vec()),
-            
-            // Page Up/Down
-            KeyCode::PageUp => Some(b"\x1b[5~".to_vec()),
-            KeyCode::PageDown => Some(b"\x1b[6~".to_vec()),
-            
-            // Function keys
-            KeyCode::F1 => Some(b"\x1bOP".to_vec()),
-            KeyCode::F2 => Some(b"\x1bOQ".to_vec()),
-            KeyCode::F3 => Some(b"\x1bOR".to_vec()),
-            KeyCode::F4 => Some(b"\x1bOS".to_vec()),
-            KeyCode::F5 => Some(b"\x1b[15~".to_vec()),
-            KeyCode::F6 => Some(b"\x1b[17~".to_vec()),
-            KeyCode::F7 => Some(b"\x1b[18~".to_vec()),
-            KeyCode::F8 => Some(b"\x1b[19~".to_vec()),
-            KeyCode::F9 => Some(b"\x1b[20~".to_vec()),
-            KeyCode::F10 => Some(b"\x1b[21~".to_vec()),
-            KeyCode::F11 => Some(b"\x1b[23~".to_vec()),
-            KeyCode::F12 => Some(b"\x1b[24~".to_vec()),
-            
-            // Control combinations that don't interfere with app shortcuts
-            KeyCode::KeyA if modifiers.control => Some(b"\x01".to_vec()), // Ctrl+A (Home)
-            KeyCode::KeyB if modifiers.control => Some(b"\x02".to_vec()), // Ctrl+B (Left)
-            KeyCode::KeyC if modifiers.control => Some(b"\x03".to_vec()), // Ctrl+C (SIGINT)
-            KeyCode::KeyD if modifiers.control => Some(b"\x04".to_vec()), // Ctrl+D (EOF)
-            KeyCode::KeyE if modifiers.control => Some(b"\x05".to_vec()), // Ctrl+E (End)
-            KeyCode::KeyF if modifiers.control => Some(b"\x06".to_vec()), // Ctrl+F (Right)
-            KeyCode::KeyG if modifiers.control => Some(b"\x07".to_vec()), // Ctrl+G (Bell)
-            KeyCode::KeyH if modifiers.control => Some(b"\x08".to_vec()), // Ctrl+H (Backspace)
-            KeyCode::KeyI if modifiers.control => Some(b"\x09".to_vec()), // Ctrl+I (Tab)
-            KeyCode::KeyJ if modifiers.control => Some(b"\x0a".to_vec()), // Ctrl+J (LF)
-            KeyCode::KeyK if modifiers.control => Some(b"\x0b".to_vec()), // Ctrl+K (VT)
-            KeyCode::KeyL if modifiers.control => Some(b"\x0c".to_vec()), // Ctrl+L (Clear)
-            KeyCode::KeyM if modifiers.control => Some(b"\x0d".to_vec()), // Ctrl+M (CR)
-            KeyCode::KeyN if modifiers.control => Some(b"\x0e".to_vec()), // Ctrl+N (Down)
-            KeyCode::KeyO if modifiers.control => Some(b"\x0f".to_vec()), // Ctrl+O 
-            KeyCode::KeyP if modifiers.control => Some(b"\x10".to_vec()), // Ctrl+P (Up)
-            KeyCode::KeyR if modifiers.control => Some(b"\x12".to_vec()), // Ctrl+R (Search)
-            KeyCode::KeyU if modifiers.control => Some(b"\x15".to_vec()), // Ctrl+U (Kill line)
-            KeyCode::KeyV if modifiers.control => Some(b"\x16".to_vec()), // Ctrl+V 
-            KeyCode::KeyX if modifiers.control => Some(b"\x18".to_vec()), // Ctrl+X
-            KeyCode::KeyY if modifiers.control => Some(b"\x19".to_vec()), // Ctrl+Y
-            KeyCode::KeyZ if modifiers.control => Some(b"\x1a".to_vec()), // Ctrl+Z (SIGTSTP)
-            
-            _ => None, // Most printable keys handled by text input event
+    /// Encode a key for every input recipient using that terminal's own modes
+    /// (APP_CURSOR, kitty flags, ...) and write it; false when nothing was sent
+    fn write_key(&mut self, key_event: &KeyEvent, event_type: KeyEventType) -> bool {
+        let Some(key) = crate::input::TermKey::from_key_code(key_event.key_code) else {
+            return false;
+        };
+        let mut written = false;
+        for terminal_id in self.state.input_recipients() {
+            let Some(backend) = self.state.terminals.get_mut(&terminal_id) else {
+                continue;
+            };
+            if let Some(bytes) = crate::input::encode_key_event(key, key_event.modifiers, event_type, backend.mode()) {
+                backend.process_command(crate::terminal::BackendCommand::Write(bytes));
+                written = true;
+            }
         }
+        written
     }
     
-
-    
-    /// Simulate command execution for better user experience
+    /// Write the same bytes to the given terminals
+    fn write_to_terminals(&mut self, terminal_ids: &[u64], bytes: &[u8]) {
+        for terminal_id in terminal_ids {
+            if let Some(backend) = self.state.terminals.get_mut(terminal_id) {
+                backend.process_command(crate::terminal::BackendCommand::Write(bytes.to_vec()));
+            }
+        }
+    }
     
+    fn key_event_type(key_event: &KeyEvent) -> KeyEventType {
+        if key_event.is_repeat { KeyEventType::Repeat } else { KeyEventType::Press }
+    }
     
+
     /// Quit the application
-    fn quit_application(&mut self, _cx: &mut Cx) {
+    fn quit_application(&mut self, cx: &mut Cx) {
         ::log::info!("Quitting ModernTerm");
-        std::process::exit(0);
+        // Leave the event loop once this event is handled, like closing the window does
+        cx.quit();
     }
 }
 
@@ -1082,28 +1547,9 @@ impl Default for App {
             ui: WidgetRef::default(),
             state,
             pty_signal,
+            config_signal: SignalToUI::new(),
+            config_watcher: None,
+            clipboard: ClipboardReader::default(),
         }
     }
 }
-
-// Helper trait for key code to digit conversion
-trait KeyCodeExt {
-    fn to_digit(&self) -> Option<u32>;
-}
-
-impl KeyCodeExt for KeyCode {
-    fn to_digit(&self) -> Option<u32> {
-        match self {
-            KeyCode::Key1 => Some(1),
-            KeyCode::Key2 => Some(2),
-            KeyCode::Key3 => Some(3),
-            KeyCode::Key4 => Some(4),
-            KeyCode::Key5 => Some(5),
-            KeyCode::Key6 => Some(6),
-            KeyCode::Key7 => Some(7),
-            KeyCode::Key8 => Some(8),
-            KeyCode::Key9 => Some(9),
-            _ => None,
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,155 @@
+// ModernTerm - Clipboard Paste
+// Sanitizes pasted text and wraps it for bracketed paste mode
+
+use makepad_widgets::SignalToUI;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// Bracketed paste delimiters (DECSET 2004)
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// Turn clipboard text into the bytes written to the PTY.
+///
+/// Control characters other than tab/newline are dropped so pasted text can't
+/// smuggle escape sequences (including a fake `ESC[201~` that would end the
+/// bracket early); newlines become CR like typed Enter.
+pub fn paste_bytes(text: &str, bracketed: bool) -> Vec<u8> {
+    let normalized = text.replace("\r\n", "\r").replace('\n', "\r");
+    let sanitized: String = normalized
+        .chars()
+        .filter(|&c| c == '\t' || c == '\r' || !c.is_control())
+        .collect();
+
+    if !bracketed {
+        return sanitized.into_bytes();
+    }
+    let mut bytes = Vec::with_capacity(sanitized.len() + PASTE_START.len() + PASTE_END.len());
+    bytes.extend_from_slice(PASTE_START);
+    bytes.extend_from_slice(sanitized.as_bytes());
+    bytes.extend_from_slice(PASTE_END);
+    bytes
+}
+
+/// Number of lines the paste would submit to a shell (0 for a single unterminated line)
+pub fn line_breaks(text: &str) -> usize {
+    text.replace("\r\n", "\n").chars().filter(|&c| c == '\n' || c == '\r').count()
+}
+
+/// A finished read: the request's tag and the text (None: no clipboard tool worked)
+pub type ClipboardResult<T> = (T, Option<String>);
+
+/// Runs `read_clipboard` on worker threads so a missing or hung tool never blocks the UI.
+///
+/// Each request carries a tag that comes back with its text through `take_results`.
+pub struct ClipboardReader<T> {
+    signal: SignalToUI,
+    results: Arc<Mutex<Vec<ClipboardResult<T>>>>,
+}
+
+impl<T> Default for ClipboardReader<T> {
+    fn default() -> Self {
+        Self { signal: SignalToUI::new(), results: Arc::new(Mutex::new(Vec::new())) }
+    }
+}
+
+impl<T: Send + 'static> ClipboardReader<T> {
+    /// Start reading the clipboard; the UI is woken when the text is ready
+    pub fn request(&self, tag: T) {
+        let signal = self.signal.clone();
+        let results = self.results.clone();
+        let spawned = std::thread::Builder::new()
+            .name("clipboard_read".to_string())
+            .spawn(move || {
+                let text = read_clipboard();
+                results.lock().unwrap().push((tag, text));
+                signal.set();
+            });
+        if let Err(e) = spawned {
+            ::log::error!("Failed to start clipboard reader: {}", e);
+        }
+    }
+
+    /// Reads that finished since the last call
+    pub fn take_results(&self) -> Vec<ClipboardResult<T>> {
+        if !self.signal.check_and_clear() {
+            return Vec::new();
+        }
+        std::mem::take(&mut *self.results.lock().unwrap())
+    }
+}
+
+/// Read the clipboard through the platform's command line tools (blocking).
+///
+/// Makepad only fetches the clipboard itself on Ctrl/Cmd+V, so other paste
+/// chords (Shift+Insert) fall back to pbpaste / wl-paste / xclip / xsel.
+/// Call it through `ClipboardReader` from the UI thread.
+pub fn read_clipboard() -> Option<String> {
+    let candidates: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbpaste", &[])]
+    } else if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        &[
+            ("wl-paste", &["--no-newline"]),
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ]
+    } else {
+        &[
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ]
+    };
+
+    candidates.iter().find_map(|(program, args)| {
+        let output = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paste_bytes_reference_table() {
+        // (clipboard text, bracketed paste, bytes sent to the PTY)
+        let table: &[(&str, bool, &[u8])] = &[
+            ("ls -la", false, b"ls -la"),
+            ("ls -la", true, b"\x1b[200~ls -la\x1b[201~"),
+            // Line endings become CR like typed Enter
+            ("a\nb\n", false, b"a\rb\r"),
+            ("a\r\nb", false, b"a\rb"),
+            ("a\rb", false, b"a\rb"),
+            ("a\r\nb", true, b"\x1b[200~a\rb\x1b[201~"),
+            ("\tindent", false, b"\tindent"),
+            // Escape sequences lose their ESC
+            ("\x1b[31mred", false, b"[31mred"),
+            ("echo \x1b]0;title\x07", false, b"echo ]0;title"),
+            // A fake end marker can't close the bracket early
+            ("x\x1b[201~; rm -rf ~\n", true, b"\x1b[200~x[201~; rm -rf ~\r\x1b[201~"),
+            // Other C0 controls, DEL and C1 controls are dropped
+            ("a\x03\x04\x08\x7fb", false, b"ab"),
+            ("a\u{9b}31mb", false, b"a31mb"),
+            ("한글 ✓", false, "한글 ✓".as_bytes()),
+            ("", true, b"\x1b[200~\x1b[201~"),
+        ];
+        for (text, bracketed, expected) in table {
+            assert_eq!(paste_bytes(text, *bracketed), expected.to_vec(), "{:?} bracketed={}", text, bracketed);
+        }
+    }
+
+    #[test]
+    fn line_break_counts() {
+        assert_eq!(line_breaks("one line"), 0);
+        assert_eq!(line_breaks("a\r\nb\r\n"), 2);
+        assert_eq!(line_breaks("a\nb\rc"), 2);
+    }
+}
@@ -0,0 +1,379 @@
+// ModernTerm - Configuration Loader
+// Reads AppConfig from $XDG_CONFIG_HOME/modernterm/config.toml and merges it over defaults
+
+use crate::types::{AppConfig, AppearanceConfig, BehaviorConfig, KeyboardConfig, BUNDLED_FONT_FAMILY};
+use makepad_widgets::SignalToUI;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Directory name used below the XDG config home
+const CONFIG_DIR_NAME: &str = "modernterm";
+/// File name of the main configuration file
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// A problem found while reading the configuration file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDiagnostic {
+    /// 1-based line number in the config file, if known
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    pub(crate) fn new(line: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Result of loading the configuration: always usable, plus any diagnostics
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub config: AppConfig,
+    /// Path the config was read from (None when no file exists)
+    pub path: Option<PathBuf>,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+/// Resolve the ModernTerm config directory following the XDG base directory spec
+pub fn config_dir() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join(CONFIG_DIR_NAME))
+}
+
+/// Resolve the config file path
+pub fn config_path() -> Option<PathBuf> {
+    Some(config_dir()?.join(CONFIG_FILE_NAME))
+}
+
+/// Load the config from the default XDG location, falling back to defaults
+pub fn load_config() -> LoadedConfig {
+    match config_path() {
+        Some(path) if path.exists() => load_config_from(&path),
+        _ => LoadedConfig {
+            config: AppConfig::default(),
+            path: None,
+            diagnostics: Vec::new(),
+        },
+    }
+}
+
+/// Load the config from a specific file
+pub fn load_config_from(path: &Path) -> LoadedConfig {
+    match read_config(path) {
+        Ok((config, diagnostics)) => LoadedConfig {
+            config,
+            path: Some(path.to_path_buf()),
+            diagnostics,
+        },
+        Err(diagnostic) => LoadedConfig {
+            config: AppConfig::default(),
+            path: Some(path.to_path_buf()),
+            diagnostics: vec![diagnostic],
+        },
+    }
+}
+
+/// Read and parse a config file.
+///
+/// Errors only when the file can't be read or isn't valid TOML at all; the
+/// caller decides whether to fall back to defaults or keep the current config.
+pub fn read_config(path: &Path) -> Result<(AppConfig, Vec<ConfigDiagnostic>), ConfigDiagnostic> {
+    let source = std::fs::read_to_string(path).map_err(|e| {
+        ConfigDiagnostic::new(None, format!("failed to read {}: {}", path.display(), e))
+    })?;
+    parse_config(&source)
+}
+
+/// Parse config source, merging it over the defaults.
+///
+/// Sections that fail to deserialize keep their default values so one typo
+/// doesn't throw away the rest of the file.
+pub fn parse_config(source: &str) -> Result<(AppConfig, Vec<ConfigDiagnostic>), ConfigDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let table = source
+        .parse::<toml::Table>()
+        .map_err(|e| diagnostic_from_toml_error(source, &e))?;
+
+    report_unknown_keys(source, &table, &mut diagnostics);
+
+    let config = match toml::from_str::<AppConfig>(source) {
+        Ok(config) => config,
+        Err(e) => {
+            diagnostics.push(diagnostic_from_toml_error(source, &e));
+            AppConfig {
+                appearance: section_or_default::<AppearanceConfig>(&table, "appearance"),
+                behavior: section_or_default::<BehaviorConfig>(&table, "behavior"),
+                keyboard: section_or_default::<KeyboardConfig>(&table, "keyboard"),
+            }
+        }
+    };
+
+    if !config.appearance.font_family.trim().eq_ignore_ascii_case(BUNDLED_FONT_FAMILY) {
+        let path = ["appearance".to_string(), "font_family".to_string()];
+        diagnostics.push(ConfigDiagnostic::new(
+            find_key_line(source, &path),
+            format!(
+                "unsupported font_family `{}`: only the bundled {} font is available",
+                config.appearance.font_family, BUNDLED_FONT_FAMILY
+            ),
+        ));
+    }
+
+    Ok((config, diagnostics))
+}
+
+/// Which parts of the config changed between two loads
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConfigChanges {
+    pub font: bool,
+    pub theme: bool,
+    pub cursor: bool,
+    pub scrollback: bool,
+    pub behavior: bool,
+    pub keyboard: bool,
+}
+
+impl ConfigChanges {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Diff two configs section by section
+pub fn diff_config(old: &AppConfig, new: &AppConfig) -> ConfigChanges {
+    ConfigChanges {
+        font: old.appearance.font_family != new.appearance.font_family
+            || old.appearance.font_size != new.appearance.font_size,
+        theme: old.appearance.theme != new.appearance.theme,
+        cursor: old.appearance.cursor_blink_interval != new.appearance.cursor_blink_interval,
+        scrollback: old.behavior.scrollback_lines != new.behavior.scrollback_lines,
+        behavior: old.behavior != new.behavior,
+        keyboard: old.keyboard != new.keyboard,
+    }
+}
+
+/// Polls the config file and signals the UI when it changes.
+///
+/// Polling (rather than inotify/FSEvents) keeps us dependency-free and also
+/// catches editors that replace the file instead of writing in place.
+pub struct ConfigWatcher {
+    stop: Arc<AtomicBool>,
+}
+
+impl ConfigWatcher {
+    const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn spawn(path: PathBuf, signal: SignalToUI) -> std::io::Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        std::thread::Builder::new()
+            .name("config_watcher".to_string())
+            .spawn(move || {
+                let mut last_stamp = file_stamp(&path);
+                while !thread_stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(Self::POLL_INTERVAL);
+                    let stamp = file_stamp(&path);
+                    if stamp != last_stamp {
+                        last_stamp = stamp;
+                        signal.set();
+                    }
+                }
+            })?;
+        Ok(Self { stop })
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Modification time + length, None while the file doesn't exist
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn section_or_default<T: DeserializeOwned + Default>(table: &toml::Table, name: &str) -> T {
+    table
+        .get(name)
+        .and_then(|value| value.clone().try_into::<T>().ok())
+        .unwrap_or_default()
+}
+
+fn diagnostic_from_toml_error(source: &str, error: &toml::de::Error) -> ConfigDiagnostic {
+    let line = error.span().map(|span| line_of_offset(source, span.start));
+    ConfigDiagnostic::new(line, error.message().trim().to_string())
+}
+
+/// Compare the parsed file against the default config and report keys we don't know
+fn report_unknown_keys(source: &str, table: &toml::Table, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    let known = match toml::Value::try_from(AppConfig::default()) {
+        Ok(toml::Value::Table(known)) => known,
+        _ => return,
+    };
+    let mut path = Vec::new();
+    collect_unknown_keys(source, table, &known, &mut path, diagnostics);
+}
+
+fn collect_unknown_keys(
+    source: &str,
+    table: &toml::Table,
+    known: &toml::Table,
+    path: &mut Vec<String>,
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    for (key, value) in table {
+        path.push(key.clone());
+        match known.get(key) {
+            None => diagnostics.push(ConfigDiagnostic::new(
+                find_key_line(source, path),
+                format!("unknown key `{}`", path.join(".")),
+            )),
+            // Empty tables in the defaults (keyboard.bindings) are free-form maps
+            Some(toml::Value::Table(known_child)) if known_child.is_empty() => {}
+            Some(toml::Value::Table(known_child)) => {
+                if let toml::Value::Table(child) = value {
+                    collect_unknown_keys(source, child, known_child, path, diagnostics);
+                }
+            }
+            Some(_) => {}
+        }
+        path.pop();
+    }
+}
+
+/// Best-effort lookup of the line defining a dotted key path
+fn find_key_line(source: &str, path: &[String]) -> Option<usize> {
+    let mut header: Vec<String> = Vec::new();
+    for (index, raw_line) in source.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.starts_with('[') {
+            let name = line.trim_start_matches('[').split(']').next().unwrap_or("");
+            header = split_key_path(name);
+            if header == path {
+                return Some(index + 1);
+            }
+            continue;
+        }
+        if let Some((key, _)) = line.split_once('=') {
+            let mut full = header.clone();
+            full.extend(split_key_path(key));
+            if full == path {
+                return Some(index + 1);
+            }
+        }
+    }
+    None
+}
+
+fn split_key_path(key: &str) -> Vec<String> {
+    key.split('.')
+        .map(|part| part.trim().trim_matches('"').trim_matches('\'').to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn line_of_offset(source: &str, offset: usize) -> usize {
+    let offset = offset.min(source.len());
+    source.as_bytes()[..offset].iter().filter(|&&b| b == b'\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FontMetrics;
+
+    #[test]
+    fn malformed_toml_reports_its_line() {
+        let error = parse_config("[appearance]\nfont_size = 12\ntheme = \"dark\n").unwrap_err();
+        assert_eq!(error.line, Some(3));
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_with_lines() {
+        let source = "\
+[appearance]
+font_size = 12.0
+font_weight = \"bold\"
+
+[behavior]
+scrollback_lines = 500
+
+[extras]
+x = 1
+
+[keyboard.bindings]
+\"Ctrl+R\" = \"Passthrough\"
+";
+        let (config, diagnostics) = parse_config(source).unwrap();
+        assert_eq!(config.appearance.font_size, 12.0);
+        assert_eq!(config.behavior.scrollback_lines, 500);
+        assert_eq!(
+            diagnostics,
+            vec![
+                ConfigDiagnostic::new(Some(3), "unknown key `appearance.font_weight`"),
+                ConfigDiagnostic::new(Some(8), "unknown key `extras`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn type_errors_fall_back_to_section_defaults() {
+        let source = "[appearance]\nfont_size = \"big\"\n\n[behavior]\nscrollback_lines = 500\n";
+        let (config, diagnostics) = parse_config(source).unwrap();
+        assert_eq!(config.appearance, AppearanceConfig::default());
+        assert_eq!(config.behavior.scrollback_lines, 500);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(2));
+    }
+
+    #[test]
+    fn unsupported_font_family_is_reported() {
+        for family in ["JetBrains Mono", "Comic Mono"] {
+            let source = format!("[appearance]\nfont_family = \"{}\"\n", family);
+            let (config, diagnostics) = parse_config(&source).unwrap();
+            assert_eq!(config.appearance.font_family, family);
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].line, Some(2));
+        }
+
+        let (_, diagnostics) = parse_config("[appearance]\nfont_family = \"d2coding\"\n").unwrap();
+        assert!(diagnostics.is_empty());
+        let (_, diagnostics) = parse_config("").unwrap();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn default_cells_match_the_drawn_face() {
+        let appearance = AppearanceConfig::default();
+        assert_eq!(appearance.font_family, BUNDLED_FONT_FAMILY);
+        // D2Coding is half an em wide and one em tall; 14pt = 18.67px
+        let cell = appearance.cell_size();
+        assert_eq!((cell.width, cell.height), (9.0, 19.0));
+        let pixels = appearance.font_size * 4.0 / 3.0;
+        assert_eq!(cell.width, (pixels * FontMetrics::D2CODING.advance).round());
+        assert_eq!(cell.height, (pixels * FontMetrics::D2CODING.line_height).round());
+    }
+}
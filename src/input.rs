@@ -0,0 +1,762 @@
+// ModernTerm - Keyboard Encoding
+// Turns key presses into the byte sequences an xterm-compatible terminal sends
+
+use alacritty_terminal::term::TermMode;
+use makepad_widgets::{KeyCode, KeyModifiers};
+
+/// A key as the terminal sees it.
+///
+/// Separate from makepad's `KeyCode` because that stops at F12 and has no
+/// notion of the character a key produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermKey {
+    /// A key producing a character, given unshifted (US layout)
+    Char(char),
+    Enter,
+    Tab,
+    Backspace,
+    Escape,
+    Insert,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+    /// F1..=F24
+    F(u8),
+    Keypad(KeypadKey),
+    /// Modifier and lock keys; only reported by the kitty protocol
+    Shift,
+    Control,
+    Alt,
+    Super,
+    CapsLock,
+    NumLock,
+    ScrollLock,
+    PrintScreen,
+    Pause,
+}
+
+/// Press, auto-repeat or release (kitty REPORT_EVENT_TYPES)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEventType {
+    Press,
+    Repeat,
+    Release,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeypadKey {
+    Digit(u8),
+    Decimal,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Equals,
+    Enter,
+}
+
+impl TermKey {
+    pub fn from_key_code(key_code: KeyCode) -> Option<Self> {
+        let key = match key_code {
+            KeyCode::KeyA => TermKey::Char('a'),
+            KeyCode::KeyB => TermKey::Char('b'),
+            KeyCode::KeyC => TermKey::Char('c'),
+            KeyCode::KeyD => TermKey::Char('d'),
+            KeyCode::KeyE => TermKey::Char('e'),
+            KeyCode::KeyF => TermKey::Char('f'),
+            KeyCode::KeyG => TermKey::Char('g'),
+            KeyCode::KeyH => TermKey::Char('h'),
+            KeyCode::KeyI => TermKey::Char('i'),
+            KeyCode::KeyJ => TermKey::Char('j'),
+            KeyCode::KeyK => TermKey::Char('k'),
+            KeyCode::KeyL => TermKey::Char('l'),
+            KeyCode::KeyM => TermKey::Char('m'),
+            KeyCode::KeyN => TermKey::Char('n'),
+            KeyCode::KeyO => TermKey::Char('o'),
+            KeyCode::KeyP => TermKey::Char('p'),
+            KeyCode::KeyQ => TermKey::Char('q'),
+            KeyCode::KeyR => TermKey::Char('r'),
+            KeyCode::KeyS => TermKey::Char('s'),
+            KeyCode::KeyT => TermKey::Char('t'),
+            KeyCode::KeyU => TermKey::Char('u'),
+            KeyCode::KeyV => TermKey::Char('v'),
+            KeyCode::KeyW => TermKey::Char('w'),
+            KeyCode::KeyX => TermKey::Char('x'),
+            KeyCode::KeyY => TermKey::Char('y'),
+            KeyCode::KeyZ => TermKey::Char('z'),
+            KeyCode::Key0 => TermKey::Char('0'),
+            KeyCode::Key1 => TermKey::Char('1'),
+            KeyCode::Key2 => TermKey::Char('2'),
+            KeyCode::Key3 => TermKey::Char('3'),
+            KeyCode::Key4 => TermKey::Char('4'),
+            KeyCode::Key5 => TermKey::Char('5'),
+            KeyCode::Key6 => TermKey::Char('6'),
+            KeyCode::Key7 => TermKey::Char('7'),
+            KeyCode::Key8 => TermKey::Char('8'),
+            KeyCode::Key9 => TermKey::Char('9'),
+            KeyCode::Space => TermKey::Char(' '),
+            KeyCode::Minus => TermKey::Char('-'),
+            KeyCode::Equals => TermKey::Char('='),
+            KeyCode::LBracket => TermKey::Char('['),
+            KeyCode::RBracket => TermKey::Char(']'),
+            KeyCode::Backslash => TermKey::Char('\\'),
+            KeyCode::Semicolon => TermKey::Char(';'),
+            KeyCode::Quote => TermKey::Char('\''),
+            KeyCode::Comma => TermKey::Char(','),
+            KeyCode::Period => TermKey::Char('.'),
+            KeyCode::Slash => TermKey::Char('/'),
+            KeyCode::Backtick => TermKey::Char('`'),
+            KeyCode::ReturnKey => TermKey::Enter,
+            KeyCode::Tab => TermKey::Tab,
+            KeyCode::Backspace => TermKey::Backspace,
+            KeyCode::Escape => TermKey::Escape,
+            KeyCode::Insert => TermKey::Insert,
+            KeyCode::Delete => TermKey::Delete,
+            KeyCode::Home => TermKey::Home,
+            KeyCode::End => TermKey::End,
+            KeyCode::PageUp => TermKey::PageUp,
+            KeyCode::PageDown => TermKey::PageDown,
+            KeyCode::ArrowUp => TermKey::Up,
+            KeyCode::ArrowDown => TermKey::Down,
+            KeyCode::ArrowLeft => TermKey::Left,
+            KeyCode::ArrowRight => TermKey::Right,
+            KeyCode::F1 => TermKey::F(1),
+            KeyCode::F2 => TermKey::F(2),
+            KeyCode::F3 => TermKey::F(3),
+            KeyCode::F4 => TermKey::F(4),
+            KeyCode::F5 => TermKey::F(5),
+            KeyCode::F6 => TermKey::F(6),
+            KeyCode::F7 => TermKey::F(7),
+            KeyCode::F8 => TermKey::F(8),
+            KeyCode::F9 => TermKey::F(9),
+            KeyCode::F10 => TermKey::F(10),
+            KeyCode::F11 => TermKey::F(11),
+            KeyCode::F12 => TermKey::F(12),
+            KeyCode::Numpad0 => TermKey::Keypad(KeypadKey::Digit(0)),
+            KeyCode::Numpad1 => TermKey::Keypad(KeypadKey::Digit(1)),
+            KeyCode::Numpad2 => TermKey::Keypad(KeypadKey::Digit(2)),
+            KeyCode::Numpad3 => TermKey::Keypad(KeypadKey::Digit(3)),
+            KeyCode::Numpad4 => TermKey::Keypad(KeypadKey::Digit(4)),
+            KeyCode::Numpad5 => TermKey::Keypad(KeypadKey::Digit(5)),
+            KeyCode::Numpad6 => TermKey::Keypad(KeypadKey::Digit(6)),
+            KeyCode::Numpad7 => TermKey::Keypad(KeypadKey::Digit(7)),
+            KeyCode::Numpad8 => TermKey::Keypad(KeypadKey::Digit(8)),
+            KeyCode::Numpad9 => TermKey::Keypad(KeypadKey::Digit(9)),
+            KeyCode::NumpadDecimal => TermKey::Keypad(KeypadKey::Decimal),
+            KeyCode::NumpadAdd => TermKey::Keypad(KeypadKey::Add),
+            KeyCode::NumpadSubtract => TermKey::Keypad(KeypadKey::Subtract),
+            KeyCode::NumpadMultiply => TermKey::Keypad(KeypadKey::Multiply),
+            KeyCode::NumpadDivide => TermKey::Keypad(KeypadKey::Divide),
+            KeyCode::NumpadEquals => TermKey::Keypad(KeypadKey::Equals),
+            KeyCode::NumpadEnter => TermKey::Keypad(KeypadKey::Enter),
+            KeyCode::Shift => TermKey::Shift,
+            KeyCode::Control => TermKey::Control,
+            KeyCode::Alt => TermKey::Alt,
+            KeyCode::Logo => TermKey::Super,
+            KeyCode::Capslock => TermKey::CapsLock,
+            KeyCode::Numlock => TermKey::NumLock,
+            KeyCode::ScrollLock => TermKey::ScrollLock,
+            KeyCode::PrintScreen => TermKey::PrintScreen,
+            KeyCode::Pause => TermKey::Pause,
+            _ => return None,
+        };
+        Some(key)
+    }
+}
+
+/// Encode a key event for the PTY, picking the kitty protocol when the program
+/// pushed keyboard enhancement flags (`CSI > flags u`) and xterm otherwise.
+pub fn encode_key_event(key: TermKey, mods: KeyModifiers, event: KeyEventType, mode: TermMode) -> Option<Vec<u8>> {
+    if mode.intersects(TermMode::KITTY_KEYBOARD_PROTOCOL) {
+        return encode_kitty(key, mods, event, mode);
+    }
+    match event {
+        KeyEventType::Press | KeyEventType::Repeat => encode_key(key, mods, mode),
+        // Legacy encodings have no key release
+        KeyEventType::Release => None,
+    }
+}
+
+/// Whether TextInput must be dropped because key presses already carry the text
+pub fn keys_report_text(mode: TermMode) -> bool {
+    mode.contains(TermMode::REPORT_ALL_KEYS_AS_ESC)
+}
+
+/// Encode a key press the way xterm does (modifyCursorKeys=2, modifyOtherKeys=0).
+///
+/// Returns None for plain printable keys: those arrive as TextInput (which
+/// also covers IME composition) and must not be sent twice.
+pub fn encode_key(key: TermKey, mods: KeyModifiers, mode: TermMode) -> Option<Vec<u8>> {
+    let param = modifier_param(mods);
+    let app_cursor = mode.contains(TermMode::APP_CURSOR);
+
+    let bytes = match key {
+        TermKey::Up => cursor_key(b'A', param, app_cursor),
+        TermKey::Down => cursor_key(b'B', param, app_cursor),
+        TermKey::Right => cursor_key(b'C', param, app_cursor),
+        TermKey::Left => cursor_key(b'D', param, app_cursor),
+        TermKey::Home => cursor_key(b'H', param, app_cursor),
+        TermKey::End => cursor_key(b'F', param, app_cursor),
+        TermKey::Insert => tilde_key(2, param),
+        TermKey::Delete => tilde_key(3, param),
+        TermKey::PageUp => tilde_key(5, param),
+        TermKey::PageDown => tilde_key(6, param),
+
+        // F1-F4 are SS3 P..S, with modifiers CSI 1;m P..S
+        TermKey::F(n @ 1..=4) => {
+            let letter = b"PQRS"[n as usize - 1];
+            if param == 1 {
+                vec![0x1b, b'O', letter]
+            } else {
+                format!("\x1b[1;{}{}", param, letter as char).into_bytes()
+            }
+        }
+        TermKey::F(n @ 5..=12) => {
+            const CODES: [u8; 8] = [15, 17, 18, 19, 20, 21, 23, 24];
+            tilde_key(CODES[n as usize - 5], param)
+        }
+        // xterm sends F13-F24 as Shift+F1-F12 (matches terminfo kf13..kf24)
+        TermKey::F(n @ 13..=24) => {
+            return encode_key(TermKey::F(n - 12), KeyModifiers { shift: true, ..mods }, mode);
+        }
+        TermKey::F(_) => return None,
+
+        TermKey::Tab if mods.shift => with_alt(mods, b"\x1b[Z".to_vec()),
+        TermKey::Tab => with_alt(mods, b"\t".to_vec()),
+        TermKey::Backspace if mods.control => with_alt(mods, vec![0x08]),
+        TermKey::Backspace => with_alt(mods, vec![0x7f]),
+        TermKey::Enter => with_alt(mods, b"\r".to_vec()),
+        TermKey::Escape => with_alt(mods, vec![0x1b]),
+
+        TermKey::Keypad(key) => return keypad_key(key, mods, mode),
+        TermKey::Char(c) => return char_key(c, mods),
+        TermKey::Shift
+        | TermKey::Control
+        | TermKey::Alt
+        | TermKey::Super
+        | TermKey::CapsLock
+        | TermKey::NumLock
+        | TermKey::ScrollLock
+        | TermKey::PrintScreen
+        | TermKey::Pause => return None,
+    };
+    Some(bytes)
+}
+
+/// xterm modifier parameter: 1 + Shift(1) + Alt(2) + Ctrl(4) + Meta(8)
+fn modifier_param(mods: KeyModifiers) -> u8 {
+    1 + mods.shift as u8 + 2 * mods.alt as u8 + 4 * mods.control as u8 + 8 * mods.logo as u8
+}
+
+/// Arrows/Home/End: SS3 in application cursor mode, CSI otherwise, CSI 1;m with modifiers
+fn cursor_key(letter: u8, param: u8, app_cursor: bool) -> Vec<u8> {
+    if param != 1 {
+        format!("\x1b[1;{}{}", param, letter as char).into_bytes()
+    } else if app_cursor {
+        vec![0x1b, b'O', letter]
+    } else {
+        vec![0x1b, b'[', letter]
+    }
+}
+
+/// Editing and function keys: CSI n ~ or CSI n;m ~
+fn tilde_key(code: u8, param: u8) -> Vec<u8> {
+    if param == 1 {
+        format!("\x1b[{}~", code).into_bytes()
+    } else {
+        format!("\x1b[{};{}~", code, param).into_bytes()
+    }
+}
+
+/// Alt as Meta: prefix the sequence with ESC
+fn with_alt(mods: KeyModifiers, bytes: Vec<u8>) -> Vec<u8> {
+    if mods.alt {
+        let mut prefixed = Vec::with_capacity(bytes.len() + 1);
+        prefixed.push(0x1b);
+        prefixed.extend(bytes);
+        prefixed
+    } else {
+        bytes
+    }
+}
+
+/// Keypad keys only have their own encoding in application keypad mode (DECKPAM)
+fn keypad_key(key: KeypadKey, mods: KeyModifiers, mode: TermMode) -> Option<Vec<u8>> {
+    if mode.contains(TermMode::APP_KEYPAD) {
+        let letter = match key {
+            KeypadKey::Digit(digit) => b'p' + digit,
+            KeypadKey::Decimal => b'n',
+            KeypadKey::Add => b'k',
+            KeypadKey::Subtract => b'm',
+            KeypadKey::Multiply => b'j',
+            KeypadKey::Divide => b'o',
+            KeypadKey::Equals => b'X',
+            KeypadKey::Enter => b'M',
+        };
+        return Some(vec![0x1b, b'O', letter]);
+    }
+    match key {
+        KeypadKey::Enter => Some(with_alt(mods, b"\r".to_vec())),
+        // Numeric mode: the digits/operators come in as TextInput
+        _ => None,
+    }
+}
+
+/// Ctrl turns a character into its C0 control code, Alt prefixes ESC
+fn char_key(c: char, mods: KeyModifiers) -> Option<Vec<u8>> {
+    if !mods.control && !mods.alt {
+        return None;
+    }
+    let c = if mods.shift { shifted(c) } else { c };
+
+    if mods.control {
+        let code = match c {
+            'a'..='z' => c as u8 & 0x1f,
+            'A'..='Z' => c as u8 & 0x1f,
+            '@' | ' ' | '`' | '2' => 0x00,
+            '[' | '3' => 0x1b,
+            '\\' | '4' => 0x1c,
+            ']' | '5' => 0x1d,
+            '^' | '~' | '6' => 0x1e,
+            '_' | '/' | '7' => 0x1f,
+            '?' | '8' => 0x7f,
+            // No control code for this key; send the character itself
+            _ => {
+                let mut buffer = [0u8; 4];
+                return Some(with_alt(mods, c.encode_utf8(&mut buffer).as_bytes().to_vec()));
+            }
+        };
+        return Some(with_alt(mods, vec![code]));
+    }
+
+    // Alt alone: ESC + the character (X11 sends no TextInput while Alt is held)
+    let mut buffer = [0u8; 4];
+    Some(with_alt(mods, c.encode_utf8(&mut buffer).as_bytes().to_vec()))
+}
+
+/// Kitty keyboard protocol (https://sw.kovidgoyal.net/kitty/keyboard-protocol/).
+///
+/// With only DISAMBIGUATE_ESC_CODES, keys that are unambiguous in legacy mode
+/// (plain text, unmodified Enter/Tab/Backspace, unmodified cursor keys) keep
+/// their legacy bytes; REPORT_ALL_KEYS_AS_ESC turns every key into `CSI … u`.
+fn encode_kitty(key: TermKey, mods: KeyModifiers, event: KeyEventType, mode: TermMode) -> Option<Vec<u8>> {
+    let report_all = mode.contains(TermMode::REPORT_ALL_KEYS_AS_ESC);
+    let report_events = mode.contains(TermMode::REPORT_EVENT_TYPES);
+    let event = match event {
+        KeyEventType::Release if !report_events => return None,
+        KeyEventType::Repeat if !report_events => KeyEventType::Press,
+        event => event,
+    };
+    let is_press = event != KeyEventType::Release;
+    let param = modifier_param(mods);
+    let unmodified = param == 1 && event == KeyEventType::Press;
+    let sequence = KittySequence { mods: param, event, ..KittySequence::default() };
+
+    let bytes = match key {
+        TermKey::Char(c) => {
+            let produces_text = !mods.control && !mods.alt && !mods.logo;
+            if produces_text && !report_all && is_press {
+                // Plain and shifted text still arrives as TextInput
+                return None;
+            }
+            let shifted_char = shifted(c);
+            KittySequence {
+                code: c as u32,
+                alternate: (mode.contains(TermMode::REPORT_ALTERNATE_KEYS) && mods.shift && shifted_char != c)
+                    .then_some(shifted_char as u32),
+                text: (mode.contains(TermMode::REPORT_ASSOCIATED_TEXT) && produces_text && is_press)
+                    .then_some(if mods.shift { shifted_char } else { c }),
+                ..sequence
+            }
+            .csi_u()
+        }
+        TermKey::Enter | TermKey::Tab | TermKey::Backspace if !report_all => {
+            // Kept legacy when unmodified so a shell stays usable if a program
+            // dies without popping its flags; no release events for these.
+            if !is_press {
+                return None;
+            }
+            if param == 1 {
+                return encode_key(key, mods, mode);
+            }
+            KittySequence { code: legacy_code(key), ..sequence }.csi_u()
+        }
+        TermKey::Enter | TermKey::Tab | TermKey::Backspace | TermKey::Escape => {
+            KittySequence { code: legacy_code(key), ..sequence }.csi_u()
+        }
+
+        TermKey::Up | TermKey::Down | TermKey::Right | TermKey::Left | TermKey::Home | TermKey::End
+        | TermKey::F(1..=4)
+            if unmodified && !report_all =>
+        {
+            return encode_key(key, mods, mode);
+        }
+        TermKey::Up => sequence.csi_letter(b'A'),
+        TermKey::Down => sequence.csi_letter(b'B'),
+        TermKey::Right => sequence.csi_letter(b'C'),
+        TermKey::Left => sequence.csi_letter(b'D'),
+        TermKey::Home => sequence.csi_letter(b'H'),
+        TermKey::End => sequence.csi_letter(b'F'),
+        TermKey::F(1) => sequence.csi_letter(b'P'),
+        TermKey::F(2) => sequence.csi_letter(b'Q'),
+        // CSI R would clash with the cursor position report
+        TermKey::F(3) => KittySequence { code: 13, ..sequence }.csi_tilde(),
+        TermKey::F(4) => sequence.csi_letter(b'S'),
+
+        TermKey::Insert => KittySequence { code: 2, ..sequence }.csi_tilde(),
+        TermKey::Delete => KittySequence { code: 3, ..sequence }.csi_tilde(),
+        TermKey::PageUp => KittySequence { code: 5, ..sequence }.csi_tilde(),
+        TermKey::PageDown => KittySequence { code: 6, ..sequence }.csi_tilde(),
+        TermKey::F(n @ 5..=12) => {
+            const CODES: [u32; 8] = [15, 17, 18, 19, 20, 21, 23, 24];
+            KittySequence { code: CODES[n as usize - 5], ..sequence }.csi_tilde()
+        }
+        TermKey::F(n @ 13..=24) => KittySequence { code: 57376 + (n as u32 - 13), ..sequence }.csi_u(),
+        TermKey::F(_) => return None,
+
+        TermKey::Keypad(_) if !report_all => {
+            return if is_press { encode_key(key, mods, mode) } else { None };
+        }
+        TermKey::Keypad(keypad) => {
+            let code = match keypad {
+                KeypadKey::Digit(digit) => 57399 + digit as u32,
+                KeypadKey::Decimal => 57409,
+                KeypadKey::Divide => 57410,
+                KeypadKey::Multiply => 57411,
+                KeypadKey::Subtract => 57412,
+                KeypadKey::Add => 57413,
+                KeypadKey::Enter => 57414,
+                KeypadKey::Equals => 57415,
+            };
+            KittySequence { code, ..sequence }.csi_u()
+        }
+
+        // Modifier/lock keys on their own are only reported with REPORT_ALL_KEYS_AS_ESC
+        _ if !report_all => return None,
+        TermKey::Shift => KittySequence { code: 57441, ..sequence }.csi_u(),
+        TermKey::Control => KittySequence { code: 57442, ..sequence }.csi_u(),
+        TermKey::Alt => KittySequence { code: 57443, ..sequence }.csi_u(),
+        TermKey::Super => KittySequence { code: 57444, ..sequence }.csi_u(),
+        TermKey::CapsLock => KittySequence { code: 57358, ..sequence }.csi_u(),
+        TermKey::ScrollLock => KittySequence { code: 57359, ..sequence }.csi_u(),
+        TermKey::NumLock => KittySequence { code: 57360, ..sequence }.csi_u(),
+        TermKey::PrintScreen => KittySequence { code: 57361, ..sequence }.csi_u(),
+        TermKey::Pause => KittySequence { code: 57362, ..sequence }.csi_u(),
+    };
+    Some(bytes)
+}
+
+/// Unicode key code kitty uses for the keys that have C0 legacy encodings
+fn legacy_code(key: TermKey) -> u32 {
+    match key {
+        TermKey::Enter => 13,
+        TermKey::Tab => 9,
+        TermKey::Backspace => 127,
+        _ => 27,
+    }
+}
+
+/// Fields of a kitty `CSI code:alternate ; mods:event ; text u` sequence
+#[derive(Debug, Clone, Copy)]
+struct KittySequence {
+    code: u32,
+    alternate: Option<u32>,
+    mods: u8,
+    event: KeyEventType,
+    text: Option<char>,
+}
+
+impl Default for KittySequence {
+    fn default() -> Self {
+        Self { code: 0, alternate: None, mods: 1, event: KeyEventType::Press, text: None }
+    }
+}
+
+impl KittySequence {
+    fn event_code(&self) -> u8 {
+        match self.event {
+            KeyEventType::Press => 1,
+            KeyEventType::Repeat => 2,
+            KeyEventType::Release => 3,
+        }
+    }
+
+    /// "m" or "m:e"; None when both are default
+    fn modifier_field(&self) -> Option<String> {
+        match self.event_code() {
+            1 if self.mods == 1 => None,
+            1 => Some(self.mods.to_string()),
+            event => Some(format!("{}:{}", self.mods, event)),
+        }
+    }
+
+    fn csi_u(&self) -> Vec<u8> {
+        let mut sequence = format!("\x1b[{}", self.code);
+        if let Some(alternate) = self.alternate {
+            sequence.push_str(&format!(":{}", alternate));
+        }
+        match (self.modifier_field(), self.text) {
+            (modifiers, Some(text)) => {
+                let modifiers = modifiers.unwrap_or_else(|| "1".to_string());
+                sequence.push_str(&format!(";{};{}", modifiers, text as u32));
+            }
+            (Some(modifiers), None) => sequence.push_str(&format!(";{}", modifiers)),
+            (None, None) => {}
+        }
+        sequence.push('u');
+        sequence.into_bytes()
+    }
+
+    /// `CSI 1;m:e X` (or `CSI X` when there is nothing to report)
+    fn csi_letter(&self, letter: u8) -> Vec<u8> {
+        match self.modifier_field() {
+            Some(modifiers) => format!("\x1b[1;{}{}", modifiers, letter as char).into_bytes(),
+            None => vec![0x1b, b'[', letter],
+        }
+    }
+
+    /// `CSI code;m:e ~`
+    fn csi_tilde(&self) -> Vec<u8> {
+        match self.modifier_field() {
+            Some(modifiers) => format!("\x1b[{};{}~", self.code, modifiers).into_bytes(),
+            None => format!("\x1b[{}~", self.code).into_bytes(),
+        }
+    }
+}
+
+/// The character a key produces with Shift on a US layout
+fn shifted(c: char) -> char {
+    match c {
+        'a'..='z' => c.to_ascii_uppercase(),
+        '1' => '!',
+        '2' => '@',
+        '3' => '#',
+        '4' => '$',
+        '5' => '%',
+        '6' => '^',
+        '7' => '&',
+        '8' => '*',
+        '9' => '(',
+        '0' => ')',
+        '-' => '_',
+        '=' => '+',
+        '[' => '{',
+        ']' => '}',
+        '\\' => '|',
+        ';' => ':',
+        '\'' => '"',
+        ',' => '<',
+        '.' => '>',
+        '/' => '?',
+        '`' => '~',
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: KeyModifiers = KeyModifiers { shift: false, control: false, alt: false, logo: false };
+    const SHIFT: KeyModifiers = KeyModifiers { shift: true, ..NONE };
+    const CTRL: KeyModifiers = KeyModifiers { control: true, ..NONE };
+    const ALT: KeyModifiers = KeyModifiers { alt: true, ..NONE };
+    const CTRL_SHIFT: KeyModifiers = KeyModifiers { control: true, shift: true, ..NONE };
+    const CTRL_ALT: KeyModifiers = KeyModifiers { control: true, alt: true, ..NONE };
+    const ALT_SHIFT: KeyModifiers = KeyModifiers { alt: true, shift: true, ..NONE };
+
+    /// (key, modifiers, mode, xterm output)
+    fn reference_table() -> Vec<(TermKey, KeyModifiers, TermMode, &'static [u8])> {
+        let normal = TermMode::empty();
+        let app_cursor = TermMode::APP_CURSOR;
+        let app_keypad = TermMode::APP_KEYPAD;
+        vec![
+            // Cursor keys
+            (TermKey::Up, NONE, normal, b"\x1b[A"),
+            (TermKey::Down, NONE, normal, b"\x1b[B"),
+            (TermKey::Right, NONE, normal, b"\x1b[C"),
+            (TermKey::Left, NONE, normal, b"\x1b[D"),
+            (TermKey::Up, NONE, app_cursor, b"\x1bOA"),
+            (TermKey::Left, NONE, app_cursor, b"\x1bOD"),
+            (TermKey::Up, SHIFT, normal, b"\x1b[1;2A"),
+            (TermKey::Up, ALT, normal, b"\x1b[1;3A"),
+            (TermKey::Up, CTRL, normal, b"\x1b[1;5A"),
+            (TermKey::Right, CTRL, app_cursor, b"\x1b[1;5C"),
+            (TermKey::Left, CTRL_SHIFT, normal, b"\x1b[1;6D"),
+            (TermKey::Down, CTRL_ALT, normal, b"\x1b[1;7B"),
+            (TermKey::Home, NONE, normal, b"\x1b[H"),
+            (TermKey::End, NONE, normal, b"\x1b[F"),
+            (TermKey::Home, NONE, app_cursor, b"\x1bOH"),
+            (TermKey::End, SHIFT, normal, b"\x1b[1;2F"),
+            // Editing keypad
+            (TermKey::Insert, NONE, normal, b"\x1b[2~"),
+            (TermKey::Delete, NONE, normal, b"\x1b[3~"),
+            (TermKey::PageUp, NONE, normal, b"\x1b[5~"),
+            (TermKey::PageDown, NONE, normal, b"\x1b[6~"),
+            (TermKey::Delete, CTRL, normal, b"\x1b[3;5~"),
+            (TermKey::PageUp, ALT, normal, b"\x1b[5;3~"),
+            // Function keys
+            (TermKey::F(1), NONE, normal, b"\x1bOP"),
+            (TermKey::F(4), NONE, normal, b"\x1bOS"),
+            (TermKey::F(1), SHIFT, normal, b"\x1b[1;2P"),
+            (TermKey::F(2), CTRL, normal, b"\x1b[1;5Q"),
+            (TermKey::F(5), NONE, normal, b"\x1b[15~"),
+            (TermKey::F(6), NONE, normal, b"\x1b[17~"),
+            (TermKey::F(10), NONE, normal, b"\x1b[21~"),
+            (TermKey::F(11), NONE, normal, b"\x1b[23~"),
+            (TermKey::F(12), NONE, normal, b"\x1b[24~"),
+            (TermKey::F(5), CTRL, normal, b"\x1b[15;5~"),
+            (TermKey::F(12), SHIFT, normal, b"\x1b[24;2~"),
+            (TermKey::F(13), NONE, normal, b"\x1b[1;2P"),
+            (TermKey::F(16), NONE, normal, b"\x1b[1;2S"),
+            (TermKey::F(17), NONE, normal, b"\x1b[15;2~"),
+            (TermKey::F(20), NONE, normal, b"\x1b[19;2~"),
+            (TermKey::F(24), NONE, normal, b"\x1b[24;2~"),
+            (TermKey::F(13), CTRL, normal, b"\x1b[1;6P"),
+            // Tab, Backspace, Enter, Escape
+            (TermKey::Tab, NONE, normal, b"\t"),
+            (TermKey::Tab, SHIFT, normal, b"\x1b[Z"),
+            (TermKey::Tab, ALT, normal, b"\x1b\t"),
+            (TermKey::Backspace, NONE, normal, b"\x7f"),
+            (TermKey::Backspace, CTRL, normal, b"\x08"),
+            (TermKey::Backspace, ALT, normal, b"\x1b\x7f"),
+            (TermKey::Enter, NONE, normal, b"\r"),
+            (TermKey::Enter, ALT, normal, b"\x1b\r"),
+            (TermKey::Escape, NONE, normal, b"\x1b"),
+            (TermKey::Escape, ALT, normal, b"\x1b\x1b"),
+            // Ctrl + letters and symbols
+            (TermKey::Char('a'), CTRL, normal, b"\x01"),
+            (TermKey::Char('c'), CTRL, normal, b"\x03"),
+            (TermKey::Char('z'), CTRL, normal, b"\x1a"),
+            (TermKey::Char('c'), CTRL_SHIFT, normal, b"\x03"),
+            (TermKey::Char(' '), CTRL, normal, b"\x00"),
+            (TermKey::Char('2'), CTRL_SHIFT, normal, b"\x00"), // Ctrl+@
+            (TermKey::Char('2'), CTRL, normal, b"\x00"),
+            (TermKey::Char('['), CTRL, normal, b"\x1b"),
+            (TermKey::Char('\\'), CTRL, normal, b"\x1c"),
+            (TermKey::Char(']'), CTRL, normal, b"\x1d"),
+            (TermKey::Char('6'), CTRL_SHIFT, normal, b"\x1e"), // Ctrl+^
+            (TermKey::Char('/'), CTRL, normal, b"\x1f"),
+            (TermKey::Char('-'), CTRL_SHIFT, normal, b"\x1f"), // Ctrl+_
+            (TermKey::Char('8'), CTRL, normal, b"\x7f"),
+            (TermKey::Char('/'), CTRL_SHIFT, normal, b"\x7f"), // Ctrl+?
+            (TermKey::Char('a'), CTRL_ALT, normal, b"\x1b\x01"),
+            // Alt as Meta
+            (TermKey::Char('b'), ALT, normal, b"\x1bb"),
+            (TermKey::Char('b'), ALT_SHIFT, normal, b"\x1bB"),
+            (TermKey::Char('.'), ALT, normal, b"\x1b."),
+            (TermKey::Char('1'), ALT_SHIFT, normal, b"\x1b!"),
+            // Keypad
+            (TermKey::Keypad(KeypadKey::Digit(0)), NONE, app_keypad, b"\x1bOp"),
+            (TermKey::Keypad(KeypadKey::Digit(9)), NONE, app_keypad, b"\x1bOy"),
+            (TermKey::Keypad(KeypadKey::Enter), NONE, app_keypad, b"\x1bOM"),
+            (TermKey::Keypad(KeypadKey::Add), NONE, app_keypad, b"\x1bOk"),
+            (TermKey::Keypad(KeypadKey::Subtract), NONE, app_keypad, b"\x1bOm"),
+            (TermKey::Keypad(KeypadKey::Multiply), NONE, app_keypad, b"\x1bOj"),
+            (TermKey::Keypad(KeypadKey::Divide), NONE, app_keypad, b"\x1bOo"),
+            (TermKey::Keypad(KeypadKey::Decimal), NONE, app_keypad, b"\x1bOn"),
+            (TermKey::Keypad(KeypadKey::Enter), NONE, normal, b"\r"),
+        ]
+    }
+
+    #[test]
+    fn matches_xterm_reference_table() {
+        for (key, mods, mode, expected) in reference_table() {
+            assert_eq!(
+                encode_key(key, mods, mode).as_deref(),
+                Some(expected),
+                "{:?} with {:?} in {:?}",
+                key,
+                mods,
+                mode
+            );
+        }
+    }
+
+    #[test]
+    fn printable_keys_are_left_to_text_input() {
+        assert_eq!(encode_key(TermKey::Char('a'), NONE, TermMode::empty()), None);
+        assert_eq!(encode_key(TermKey::Char('a'), SHIFT, TermMode::empty()), None);
+        assert_eq!(encode_key(TermKey::Char(' '), NONE, TermMode::empty()), None);
+        assert_eq!(encode_key(TermKey::Keypad(KeypadKey::Digit(5)), NONE, TermMode::empty()), None);
+    }
+
+    #[test]
+    fn kitty_protocol_encodings() {
+        let disambiguate = TermMode::DISAMBIGUATE_ESC_CODES;
+        let all_keys = TermMode::DISAMBIGUATE_ESC_CODES | TermMode::REPORT_ALL_KEYS_AS_ESC;
+        let events = TermMode::DISAMBIGUATE_ESC_CODES | TermMode::REPORT_EVENT_TYPES;
+        let press = KeyEventType::Press;
+        let release = KeyEventType::Release;
+        type Case = (TermKey, KeyModifiers, KeyEventType, TermMode, Option<&'static [u8]>);
+        let table: Vec<Case> = vec![
+            // Disambiguate: Ctrl+I is no longer Tab, Esc is no longer a prefix
+            (TermKey::Char('i'), CTRL, press, disambiguate, Some(b"\x1b[105;5u")),
+            (TermKey::Tab, NONE, press, disambiguate, Some(b"\t")),
+            (TermKey::Tab, SHIFT, press, disambiguate, Some(b"\x1b[9;2u")),
+            (TermKey::Escape, NONE, press, disambiguate, Some(b"\x1b[27u")),
+            (TermKey::Char('b'), ALT, press, disambiguate, Some(b"\x1b[98;3u")),
+            (TermKey::Enter, CTRL, press, disambiguate, Some(b"\x1b[13;5u")),
+            (TermKey::Char('a'), NONE, press, disambiguate, None),
+            (TermKey::Char('a'), SHIFT, press, disambiguate, None),
+            (TermKey::Up, NONE, press, disambiguate, Some(b"\x1b[A")),
+            (TermKey::Up, CTRL, press, disambiguate, Some(b"\x1b[1;5A")),
+            (TermKey::F(3), NONE, press, disambiguate, Some(b"\x1bOR")),
+            (TermKey::F(3), CTRL, press, disambiguate, Some(b"\x1b[13;5~")),
+            (TermKey::F(13), NONE, press, disambiguate, Some(b"\x1b[57376u")),
+            // Event types
+            (TermKey::Char('a'), NONE, release, events, Some(b"\x1b[97;1:3u")),
+            (TermKey::Char('i'), CTRL, KeyEventType::Repeat, events, Some(b"\x1b[105;5:2u")),
+            (TermKey::Up, NONE, release, events, Some(b"\x1b[1;1:3A")),
+            (TermKey::Enter, NONE, release, events, None),
+            (TermKey::Char('a'), NONE, release, disambiguate, None),
+            // Report all keys
+            (TermKey::Char('a'), NONE, press, all_keys, Some(b"\x1b[97u")),
+            (TermKey::Char('a'), SHIFT, press, all_keys, Some(b"\x1b[97;2u")),
+            (TermKey::Enter, NONE, press, all_keys, Some(b"\x1b[13u")),
+            (TermKey::Shift, SHIFT, press, all_keys, Some(b"\x1b[57441;2u")),
+            (TermKey::Keypad(KeypadKey::Digit(1)), NONE, press, all_keys, Some(b"\x1b[57400u")),
+            (TermKey::Up, NONE, press, all_keys, Some(b"\x1b[A")),
+            // Alternate keys and associated text
+            (
+                TermKey::Char('a'),
+                SHIFT,
+                press,
+                all_keys | TermMode::REPORT_ALTERNATE_KEYS | TermMode::REPORT_ASSOCIATED_TEXT,
+                Some(b"\x1b[97:65;2;65u"),
+            ),
+        ];
+        for (key, mods, event, mode, expected) in table {
+            assert_eq!(
+                encode_key_event(key, mods, event, mode).as_deref(),
+                expected,
+                "{:?} {:?} with {:?} in {:?}",
+                event,
+                key,
+                mods,
+                mode
+            );
+        }
+    }
+
+    #[test]
+    fn legacy_mode_has_no_releases() {
+        assert_eq!(encode_key_event(TermKey::Up, NONE, KeyEventType::Release, TermMode::empty()), None);
+        assert_eq!(
+            encode_key_event(TermKey::Up, NONE, KeyEventType::Press, TermMode::empty()).as_deref(),
+            Some(&b"\x1b[A"[..])
+        );
+    }
+
+    #[test]
+    fn function_keys_past_f24_are_ignored() {
+        assert_eq!(encode_key(TermKey::F(0), NONE, TermMode::empty()), None);
+        assert_eq!(encode_key(TermKey::F(25), NONE, TermMode::empty()), None);
+    }
+}
@@ -0,0 +1,541 @@
+// ModernTerm - Keymap
+// Parses binding strings like "Ctrl+Shift+PageUp" and maps key chords to app actions
+
+use crate::config::ConfigDiagnostic;
+use crate::types::{KeyboardConfig, PaneDirection};
+use makepad_widgets::{KeyCode, KeyEvent};
+use std::fmt;
+
+/// A key plus the exact set of modifiers that must be held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub key_code: KeyCode,
+    pub control: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+
+impl KeyChord {
+    /// Parse a binding string such as "Ctrl+T", "Ctrl+Shift+PageUp" or "Super+1"
+    pub fn parse(binding: &str) -> Result<Self, String> {
+        let mut chord = KeyChord {
+            key_code: KeyCode::Unknown,
+            control: false,
+            shift: false,
+            alt: false,
+            logo: false,
+        };
+        let mut key_code = None;
+
+        for part in binding.split('+').map(str::trim) {
+            if part.is_empty() {
+                return Err(format!("empty key in binding `{}`", binding));
+            }
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.control = true,
+                "shift" => chord.shift = true,
+                "alt" | "option" | "meta" => chord.alt = true,
+                "super" | "cmd" | "command" | "logo" | "win" => chord.logo = true,
+                name => {
+                    if key_code.is_some() {
+                        return Err(format!("more than one key in binding `{}`", binding));
+                    }
+                    key_code = Some(
+                        key_code_from_name(name)
+                            .ok_or_else(|| format!("unknown key `{}` in binding `{}`", part, binding))?,
+                    );
+                }
+            }
+        }
+
+        chord.key_code = key_code.ok_or_else(|| format!("no key in binding `{}`", binding))?;
+        Ok(chord)
+    }
+
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        let modifiers = &key_event.modifiers;
+        key_event.key_code == self.key_code
+            && modifiers.control == self.control
+            && modifiers.shift == self.shift
+            && modifiers.alt == self.alt
+            && modifiers.logo == self.logo
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.control {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.logo {
+            write!(f, "Super+")?;
+        }
+        write!(f, "{:?}", self.key_code)
+    }
+}
+
+/// Named actions a key chord can trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppAction {
+    NewTab,
+    CloseTab,
+    SplitVertical,
+    SplitHorizontal,
+    ToggleGrid,
+    Quit,
+    RefreshDisplay,
+    ClearScreen,
+    ScrollUp,
+    ScrollDown,
+    ScrollPageUp,
+    ScrollPageDown,
+    /// Copy the focused terminal's selection to the clipboard
+    Copy,
+    /// Paste the clipboard into the focused terminal
+    Paste,
+    /// Switch to tab N (1-based)
+    SwitchToTab(usize),
+    /// Move the focused pane's divider in a direction
+    ResizePane(PaneDirection),
+    /// Reset every split in the active tab to equal pane sizes
+    EqualizeSplits,
+    /// Focus the nearest pane in a direction
+    FocusPane(PaneDirection),
+    /// Toggle focus back to the previously focused pane
+    FocusPreviousPane,
+    /// Close the focused pane (the whole tab if it is the only pane)
+    ClosePane,
+    /// Show only the focused pane at full size, or restore the split layout
+    ToggleZoom,
+    /// Give the active tab's grid cell more room
+    GrowGridCell,
+    /// Give the active tab's grid cell less room
+    ShrinkGridCell,
+    /// Send typed and pasted input to the broadcast targets as well
+    ToggleBroadcast,
+    /// Activate the next tab in tab order (wraps around)
+    NextTab,
+    /// Activate the previous tab in tab order (wraps around)
+    PreviousTab,
+    /// Activate the last tab, whatever the tab count
+    LastTab,
+    /// Toggle back to the tab used before the active one
+    LastUsedTab,
+    /// Move the active tab one place to the left in tab order
+    MoveTabLeft,
+    /// Move the active tab one place to the right in tab order
+    MoveTabRight,
+    /// Reopen the most recently closed tab with its layout and directories
+    ReopenClosedTab,
+    /// Edit the active tab's name (an empty name goes back to the automatic title)
+    RenameTab,
+    /// Don't handle the chord in the app, send it to the PTY instead
+    Passthrough,
+}
+
+impl AppAction {
+    /// Parse an action name from the config ("NewTab", "new_tab", "Passthrough", ...)
+    pub fn parse(name: &str) -> Option<Self> {
+        let normalized: String = name
+            .chars()
+            .filter(|c| *c != '_' && *c != '-' && !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        let action = match normalized.as_str() {
+            "newtab" => AppAction::NewTab,
+            "closetab" => AppAction::CloseTab,
+            "splitvertical" => AppAction::SplitVertical,
+            "splithorizontal" => AppAction::SplitHorizontal,
+            "togglegrid" => AppAction::ToggleGrid,
+            "quit" => AppAction::Quit,
+            "refreshdisplay" | "refresh" => AppAction::RefreshDisplay,
+            "clearscreen" | "clear" => AppAction::ClearScreen,
+            "scrollup" => AppAction::ScrollUp,
+            "scrolldown" => AppAction::ScrollDown,
+            "scrollpageup" => AppAction::ScrollPageUp,
+            "scrollpagedown" => AppAction::ScrollPageDown,
+            "copy" => AppAction::Copy,
+            "paste" => AppAction::Paste,
+            "resizepaneleft" => AppAction::ResizePane(PaneDirection::Left),
+            "resizepaneright" => AppAction::ResizePane(PaneDirection::Right),
+            "resizepaneup" => AppAction::ResizePane(PaneDirection::Up),
+            "resizepanedown" => AppAction::ResizePane(PaneDirection::Down),
+            "equalizesplits" | "equalize" => AppAction::EqualizeSplits,
+            "focuspaneleft" => AppAction::FocusPane(PaneDirection::Left),
+            "focuspaneright" => AppAction::FocusPane(PaneDirection::Right),
+            "focuspaneup" => AppAction::FocusPane(PaneDirection::Up),
+            "focuspanedown" => AppAction::FocusPane(PaneDirection::Down),
+            "focuspreviouspane" | "previouspane" => AppAction::FocusPreviousPane,
+            "closepane" => AppAction::ClosePane,
+            "togglezoom" | "zoom" | "zoompane" => AppAction::ToggleZoom,
+            "growgridcell" => AppAction::GrowGridCell,
+            "shrinkgridcell" => AppAction::ShrinkGridCell,
+            "togglebroadcast" | "broadcast" => AppAction::ToggleBroadcast,
+            "nexttab" => AppAction::NextTab,
+            "previoustab" | "prevtab" => AppAction::PreviousTab,
+            "lasttab" => AppAction::LastTab,
+            "lastusedtab" | "recenttab" => AppAction::LastUsedTab,
+            "movetableft" => AppAction::MoveTabLeft,
+            "movetabright" => AppAction::MoveTabRight,
+            "reopenclosedtab" | "reopentab" => AppAction::ReopenClosedTab,
+            "renametab" => AppAction::RenameTab,
+            "passthrough" | "unbind" | "none" => AppAction::Passthrough,
+            other => {
+                let number = other.strip_prefix("switchtotab")?.parse::<usize>().ok()?;
+                if !(1..=9).contains(&number) {
+                    return None;
+                }
+                AppAction::SwitchToTab(number)
+            }
+        };
+        Some(action)
+    }
+}
+
+/// Where a binding came from, used to decide what counts as a conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BindingSource {
+    /// Built-in default
+    Default,
+    /// One of the named KeyboardConfig fields (new_tab, close_tab, ...)
+    Named(&'static str),
+    /// An entry of the [keyboard.bindings] table
+    Table,
+}
+
+impl fmt::Display for BindingSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingSource::Default => write!(f, "built-in default"),
+            BindingSource::Named(field) => write!(f, "keyboard.{}", field),
+            BindingSource::Table => write!(f, "keyboard.bindings"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Binding {
+    chord: KeyChord,
+    action: AppAction,
+    source: BindingSource,
+}
+
+/// Chord -> action table built from KeyboardConfig
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&KeyboardConfig::default()).0
+    }
+}
+
+impl Keymap {
+    /// Built-in bindings that have no dedicated KeyboardConfig field
+    const DEFAULT_BINDINGS: &'static [(&'static str, AppAction)] = &[
+        ("Ctrl+S", AppAction::ToggleGrid),
+        ("Ctrl+Q", AppAction::Quit),
+        ("Ctrl+R", AppAction::RefreshDisplay),
+        ("Ctrl+Shift+L", AppAction::ClearScreen),
+        ("Ctrl+Shift+C", AppAction::Copy),
+        ("Ctrl+Shift+V", AppAction::Paste),
+        ("Shift+Insert", AppAction::Paste),
+        ("PageUp", AppAction::ScrollUp),
+        ("PageDown", AppAction::ScrollDown),
+        ("Shift+PageUp", AppAction::ScrollPageUp),
+        ("Shift+PageDown", AppAction::ScrollPageDown),
+        ("Ctrl+Alt+Left", AppAction::ResizePane(PaneDirection::Left)),
+        ("Ctrl+Alt+Right", AppAction::ResizePane(PaneDirection::Right)),
+        ("Ctrl+Alt+Up", AppAction::ResizePane(PaneDirection::Up)),
+        ("Ctrl+Alt+Down", AppAction::ResizePane(PaneDirection::Down)),
+        ("Ctrl+Alt+Equals", AppAction::EqualizeSplits),
+        ("Alt+Left", AppAction::FocusPane(PaneDirection::Left)),
+        ("Alt+Right", AppAction::FocusPane(PaneDirection::Right)),
+        ("Alt+Up", AppAction::FocusPane(PaneDirection::Up)),
+        ("Alt+Down", AppAction::FocusPane(PaneDirection::Down)),
+        ("Ctrl+Alt+P", AppAction::FocusPreviousPane),
+        ("Ctrl+Shift+W", AppAction::ClosePane),
+        ("Ctrl+Shift+Z", AppAction::ToggleZoom),
+        ("Ctrl+Alt+]", AppAction::GrowGridCell),
+        ("Ctrl+Alt+[", AppAction::ShrinkGridCell),
+        ("Ctrl+B", AppAction::ToggleBroadcast),
+        ("Ctrl+Tab", AppAction::NextTab),
+        ("Ctrl+Shift+Tab", AppAction::PreviousTab),
+        ("Ctrl+`", AppAction::LastUsedTab),
+        ("Ctrl+Shift+PageUp", AppAction::MoveTabLeft),
+        ("Ctrl+Shift+PageDown", AppAction::MoveTabRight),
+        ("Ctrl+Shift+T", AppAction::ReopenClosedTab),
+        ("Ctrl+Shift+R", AppAction::RenameTab),
+        ("Ctrl+1", AppAction::SwitchToTab(1)),
+        ("Ctrl+2", AppAction::SwitchToTab(2)),
+        ("Ctrl+3", AppAction::SwitchToTab(3)),
+        ("Ctrl+4", AppAction::SwitchToTab(4)),
+        ("Ctrl+5", AppAction::SwitchToTab(5)),
+        ("Ctrl+6", AppAction::SwitchToTab(6)),
+        ("Ctrl+7", AppAction::SwitchToTab(7)),
+        ("Ctrl+8", AppAction::SwitchToTab(8)),
+        // Like browsers: Ctrl+9 always goes to the last tab
+        ("Ctrl+9", AppAction::LastTab),
+    ];
+
+    /// Build the keymap: defaults, then named fields, then the bindings table.
+    ///
+    /// Returns diagnostics for unparsable bindings and conflicting chords.
+    pub fn from_config(config: &KeyboardConfig) -> (Self, Vec<ConfigDiagnostic>) {
+        let mut keymap = Keymap { bindings: Vec::new() };
+        let mut diagnostics = Vec::new();
+
+        for (binding, action) in Self::DEFAULT_BINDINGS {
+            if let Ok(chord) = KeyChord::parse(binding) {
+                keymap.bind(chord, *action, BindingSource::Default, &mut diagnostics);
+            }
+        }
+
+        let named = [
+            ("new_tab", &config.new_tab, AppAction::NewTab),
+            ("close_tab", &config.close_tab, AppAction::CloseTab),
+            ("split_vertical", &config.split_vertical, AppAction::SplitVertical),
+            ("split_horizontal", &config.split_horizontal, AppAction::SplitHorizontal),
+        ];
+        for (field, binding, action) in named {
+            // An empty string or "none" leaves the action unbound
+            if binding.trim().is_empty() || binding.trim().eq_ignore_ascii_case("none") {
+                continue;
+            }
+            match KeyChord::parse(binding) {
+                Ok(chord) => keymap.bind(chord, action, BindingSource::Named(field), &mut diagnostics),
+                Err(e) => diagnostics.push(ConfigDiagnostic::new(None, format!("keyboard.{}: {}", field, e))),
+            }
+        }
+
+        for (binding, action_name) in &config.bindings {
+            let chord = match KeyChord::parse(binding) {
+                Ok(chord) => chord,
+                Err(e) => {
+                    diagnostics.push(ConfigDiagnostic::new(None, format!("keyboard.bindings: {}", e)));
+                    continue;
+                }
+            };
+            match AppAction::parse(action_name) {
+                Some(action) => keymap.bind(chord, action, BindingSource::Table, &mut diagnostics),
+                None => diagnostics.push(ConfigDiagnostic::new(
+                    None,
+                    format!("keyboard.bindings: unknown action `{}` for `{}`", action_name, binding),
+                )),
+            }
+        }
+
+        (keymap, diagnostics)
+    }
+
+    /// Find the action bound to a key event
+    pub fn lookup(&self, key_event: &KeyEvent) -> Option<AppAction> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.chord.matches(key_event))
+            .map(|binding| binding.action)
+    }
+
+    fn bind(
+        &mut self,
+        chord: KeyChord,
+        action: AppAction,
+        source: BindingSource,
+        diagnostics: &mut Vec<ConfigDiagnostic>,
+    ) {
+        if let Some(existing) = self.bindings.iter_mut().find(|b| b.chord == chord) {
+            // The bindings table is the explicit override mechanism; anything else
+            // silently shadowing another binding is almost certainly a mistake.
+            let explicit_override = source == BindingSource::Table && existing.source != BindingSource::Table;
+            if existing.action != action && !explicit_override {
+                diagnostics.push(ConfigDiagnostic::new(
+                    None,
+                    format!(
+                        "`{}` is bound to both {:?} ({}) and {:?} ({}); using {:?}",
+                        chord, existing.action, existing.source, action, source, action
+                    ),
+                ));
+            }
+            existing.action = action;
+            existing.source = source;
+        } else {
+            self.bindings.push(Binding { chord, action, source });
+        }
+    }
+}
+
+fn key_code_from_name(name: &str) -> Option<KeyCode> {
+    let key_code = match name {
+        "a" => KeyCode::KeyA, "b" => KeyCode::KeyB, "c" => KeyCode::KeyC, "d" => KeyCode::KeyD,
+        "e" => KeyCode::KeyE, "f" => KeyCode::KeyF, "g" => KeyCode::KeyG, "h" => KeyCode::KeyH,
+        "i" => KeyCode::KeyI, "j" => KeyCode::KeyJ, "k" => KeyCode::KeyK, "l" => KeyCode::KeyL,
+        "m" => KeyCode::KeyM, "n" => KeyCode::KeyN, "o" => KeyCode::KeyO, "p" => KeyCode::KeyP,
+        "q" => KeyCode::KeyQ, "r" => KeyCode::KeyR, "s" => KeyCode::KeyS, "t" => KeyCode::KeyT,
+        "u" => KeyCode::KeyU, "v" => KeyCode::KeyV, "w" => KeyCode::KeyW, "x" => KeyCode::KeyX,
+        "y" => KeyCode::KeyY, "z" => KeyCode::KeyZ,
+        "0" => KeyCode::Key0, "1" => KeyCode::Key1, "2" => KeyCode::Key2, "3" => KeyCode::Key3,
+        "4" => KeyCode::Key4, "5" => KeyCode::Key5, "6" => KeyCode::Key6, "7" => KeyCode::Key7,
+        "8" => KeyCode::Key8, "9" => KeyCode::Key9,
+        "f1" => KeyCode::F1, "f2" => KeyCode::F2, "f3" => KeyCode::F3, "f4" => KeyCode::F4,
+        "f5" => KeyCode::F5, "f6" => KeyCode::F6, "f7" => KeyCode::F7, "f8" => KeyCode::F8,
+        "f9" => KeyCode::F9, "f10" => KeyCode::F10, "f11" => KeyCode::F11, "f12" => KeyCode::F12,
+        "up" | "arrowup" => KeyCode::ArrowUp,
+        "down" | "arrowdown" => KeyCode::ArrowDown,
+        "left" | "arrowleft" => KeyCode::ArrowLeft,
+        "right" | "arrowright" => KeyCode::ArrowRight,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "insert" | "ins" => KeyCode::Insert,
+        "delete" | "del" => KeyCode::Delete,
+        "backspace" => KeyCode::Backspace,
+        "tab" => KeyCode::Tab,
+        "enter" | "return" => KeyCode::ReturnKey,
+        "escape" | "esc" => KeyCode::Escape,
+        "space" => KeyCode::Space,
+        "minus" | "-" => KeyCode::Minus,
+        "equals" | "=" => KeyCode::Equals,
+        "comma" | "," => KeyCode::Comma,
+        "period" | "." => KeyCode::Period,
+        "slash" | "/" => KeyCode::Slash,
+        "backslash" | "\\" => KeyCode::Backslash,
+        "semicolon" | ";" => KeyCode::Semicolon,
+        "quote" | "'" => KeyCode::Quote,
+        "backtick" | "`" => KeyCode::Backtick,
+        "bracketleft" | "[" => KeyCode::LBracket,
+        "bracketright" | "]" => KeyCode::RBracket,
+        _ => return None,
+    };
+    Some(key_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use makepad_widgets::KeyModifiers;
+    use std::collections::BTreeMap;
+
+    fn chord(key_code: KeyCode, control: bool, shift: bool, alt: bool, logo: bool) -> KeyChord {
+        KeyChord { key_code, control, shift, alt, logo }
+    }
+
+    fn key(key_code: KeyCode, control: bool, shift: bool) -> KeyEvent {
+        KeyEvent {
+            key_code,
+            is_repeat: false,
+            modifiers: KeyModifiers { control, shift, ..KeyModifiers::default() },
+            time: 0.0,
+        }
+    }
+
+    #[test]
+    fn modifier_aliases_and_key_names() {
+        let table = [
+            ("Ctrl+T", chord(KeyCode::KeyT, true, false, false, false)),
+            ("control + shift + pgup", chord(KeyCode::PageUp, true, true, false, false)),
+            ("Option+Left", chord(KeyCode::ArrowLeft, false, false, true, false)),
+            ("Meta+b", chord(KeyCode::KeyB, false, false, true, false)),
+            ("Cmd+1", chord(KeyCode::Key1, false, false, false, true)),
+            ("Win+Shift+Esc", chord(KeyCode::Escape, false, true, false, true)),
+            ("Ctrl+Alt+]", chord(KeyCode::RBracket, true, false, true, false)),
+            ("Ctrl+`", chord(KeyCode::Backtick, true, false, false, false)),
+        ];
+        for (binding, expected) in table {
+            assert_eq!(KeyChord::parse(binding), Ok(expected), "{}", binding);
+        }
+    }
+
+    #[test]
+    fn malformed_bindings_are_rejected() {
+        for binding in ["Ctrl+Foo", "Ctrl+", "Ctrl", "Ctrl+A+B", ""] {
+            assert!(KeyChord::parse(binding).is_err(), "{}", binding);
+        }
+    }
+
+    #[test]
+    fn action_names() {
+        assert_eq!(AppAction::parse("NewTab"), Some(AppAction::NewTab));
+        assert_eq!(AppAction::parse("new_tab"), Some(AppAction::NewTab));
+        assert_eq!(AppAction::parse("Focus-Pane-Left"), Some(AppAction::FocusPane(PaneDirection::Left)));
+        assert_eq!(AppAction::parse("switch_to_tab_3"), Some(AppAction::SwitchToTab(3)));
+        assert_eq!(AppAction::parse("SwitchToTab10"), None);
+        assert_eq!(AppAction::parse("Frobnicate"), None);
+    }
+
+    #[test]
+    fn defaults_do_not_conflict() {
+        let (keymap, diagnostics) = Keymap::from_config(&KeyboardConfig::default());
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(keymap.lookup(&key(KeyCode::KeyV, true, true)), Some(AppAction::Paste));
+        assert_eq!(keymap.lookup(&key(KeyCode::KeyE, true, true)), Some(AppAction::SplitVertical));
+        assert_eq!(keymap.lookup(&key(KeyCode::KeyT, true, false)), Some(AppAction::NewTab));
+        assert_eq!(keymap.lookup(&key(KeyCode::KeyT, false, false)), None);
+    }
+
+    #[test]
+    fn named_field_over_a_default_is_a_conflict() {
+        let config = KeyboardConfig { split_vertical: "Ctrl+Shift+V".into(), ..KeyboardConfig::default() };
+        let (keymap, diagnostics) = Keymap::from_config(&config);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("Paste (built-in default)"), "{}", diagnostics[0]);
+        assert_eq!(keymap.lookup(&key(KeyCode::KeyV, true, true)), Some(AppAction::SplitVertical));
+    }
+
+    #[test]
+    fn bindings_table_overrides_defaults_silently() {
+        let config = KeyboardConfig {
+            bindings: BTreeMap::from([("Ctrl+Q".to_string(), "Passthrough".to_string())]),
+            ..KeyboardConfig::default()
+        };
+        let (keymap, diagnostics) = Keymap::from_config(&config);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(keymap.lookup(&key(KeyCode::KeyQ, true, false)), Some(AppAction::Passthrough));
+    }
+
+    #[test]
+    fn user_bindings_conflicting_with_each_other() {
+        let config = KeyboardConfig {
+            new_tab: "Ctrl+Y".into(),
+            close_tab: "Ctrl+Y".into(),
+            ..KeyboardConfig::default()
+        };
+        let (_, diagnostics) = Keymap::from_config(&config);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("keyboard.new_tab"), "{}", diagnostics[0]);
+
+        // Two spellings of one chord in the table
+        let config = KeyboardConfig {
+            bindings: BTreeMap::from([
+                ("Ctrl+K".to_string(), "ClearScreen".to_string()),
+                ("control+k".to_string(), "Quit".to_string()),
+            ]),
+            ..KeyboardConfig::default()
+        };
+        let (_, diagnostics) = Keymap::from_config(&config);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("keyboard.bindings"), "{}", diagnostics[0]);
+    }
+
+    #[test]
+    fn bad_table_entries_are_reported() {
+        let config = KeyboardConfig {
+            bindings: BTreeMap::from([
+                ("Ctrl+Nope".to_string(), "NewTab".to_string()),
+                ("Ctrl+K".to_string(), "Frobnicate".to_string()),
+            ]),
+            ..KeyboardConfig::default()
+        };
+        let (_, diagnostics) = Keymap::from_config(&config);
+        assert_eq!(diagnostics.len(), 2);
+    }
+}
@@ -0,0 +1,622 @@
+// ModernTerm - Pane Layout
+// Turns a PanelContent split tree into window rectangles
+
+use crate::types::{PaneDirection, PanelContent, Size, SplitDirection, ViewMode, MAX_SPLIT_RATIO, MIN_SPLIT_RATIO};
+use makepad_widgets::{dvec2, DVec2, Rect};
+
+/// Gap between two panes of a split (the divider)
+pub const DIVIDER_SIZE: f64 = 4.0;
+
+/// Padding the terminal view keeps around the grid (12px on each side)
+pub const PANE_PADDING: f64 = 24.0;
+
+/// Extra pixels on each side of a divider that still grab it
+pub const DIVIDER_GRAB_MARGIN: f64 = 3.0;
+
+/// The gap between the two halves of one split
+#[derive(Debug, Clone)]
+pub struct Divider {
+    /// Child indices from the root to the split (see PanelContent::node_at_mut)
+    pub path: Vec<usize>,
+    pub direction: SplitDirection,
+    pub rect: Rect,
+    /// The whole area of the split, used to turn a drag position into a ratio
+    pub parent: Rect,
+}
+
+impl Divider {
+    /// Whether a position is on the divider, including the grab margin
+    pub fn grabs(&self, abs: DVec2) -> bool {
+        grabs(self.rect, abs)
+    }
+
+    /// Split ratio that puts the divider's center at `abs`
+    pub fn ratio_at(&self, abs: DVec2) -> f32 {
+        let (offset, length) = match self.direction {
+            SplitDirection::Vertical => (abs.x - self.parent.pos.x, self.parent.size.x),
+            SplitDirection::Horizontal => (abs.y - self.parent.pos.y, self.parent.size.y),
+        };
+        let available = (length - DIVIDER_SIZE).max(1.0);
+        (((offset - DIVIDER_SIZE / 2.0) / available) as f32).clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO)
+    }
+}
+
+fn grabs(rect: Rect, abs: DVec2) -> bool {
+    let margin = DIVIDER_GRAB_MARGIN;
+    abs.x >= rect.pos.x - margin
+        && abs.x <= rect.pos.x + rect.size.x + margin
+        && abs.y >= rect.pos.y - margin
+        && abs.y <= rect.pos.y + rect.size.y + margin
+}
+
+/// Smallest share of the grid a row or column can be shrunk to
+pub const MIN_TRACK_SHARE: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridAxis {
+    Columns,
+    Rows,
+}
+
+/// The gutter between two grid columns or rows
+#[derive(Debug, Clone)]
+pub struct GridGutter {
+    pub axis: GridAxis,
+    /// Boundary between track `index` and `index + 1`
+    pub index: usize,
+    pub rect: Rect,
+    /// Start of track `index` and end of track `index + 1` along the axis
+    pub span: (f64, f64),
+}
+
+impl GridGutter {
+    pub fn grabs(&self, abs: DVec2) -> bool {
+        grabs(self.rect, abs)
+    }
+
+    /// Share of the two tracks that the first one gets with the gutter at `abs`
+    pub fn fraction_at(&self, abs: DVec2) -> f32 {
+        let position = match self.axis {
+            GridAxis::Columns => abs.x,
+            GridAxis::Rows => abs.y,
+        };
+        let available = (self.span.1 - self.span.0 - DIVIDER_SIZE).max(1.0);
+        (((position - self.span.0 - DIVIDER_SIZE / 2.0) / available) as f32).clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO)
+    }
+}
+
+/// Area of every tab: the whole area in Single mode, one grid cell each in Grid mode
+pub fn tab_areas(view_mode: &ViewMode, tab_order: &[u64], area: Rect) -> Vec<(u64, Rect)> {
+    match view_mode {
+        ViewMode::Single => tab_order.iter().map(|&tab_id| (tab_id, area)).collect(),
+        ViewMode::Grid { rows, cols, col_ratios, row_ratios } => {
+            let cells = grid_cells(tab_order.len(), *rows, *cols, col_ratios, row_ratios, area);
+            tab_order.iter().copied().zip(cells).collect()
+        }
+    }
+}
+
+/// Cell rectangles for `count` tabs, filled row by row.
+///
+/// A tab left alone on the last row spans the full width (3 tabs = 2 on top +
+/// 1 full width); tabs beyond rows x cols get no cell.
+pub fn grid_cells(count: usize, rows: usize, cols: usize, col_ratios: &[f32], row_ratios: &[f32], area: Rect) -> Vec<Rect> {
+    if rows == 0 || cols == 0 {
+        return Vec::new();
+    }
+    let xs = grid_edges(area.pos.x, area.size.x, cols, col_ratios);
+    let ys = grid_edges(area.pos.y, area.size.y, rows, row_ratios);
+    let cell = |(x0, x1): (f64, f64), (y0, y1): (f64, f64)| Rect { pos: dvec2(x0, y0), size: dvec2(x1 - x0, y1 - y0) };
+
+    let count = count.min(rows * cols);
+    (0..count)
+        .map(|index| {
+            let (row, col) = (index / cols, index % cols);
+            if col == 0 && index + 1 == count && row > 0 {
+                cell((xs[0].0, xs[cols - 1].1), ys[row])
+            } else {
+                cell(xs[col], ys[row])
+            }
+        })
+        .collect()
+}
+
+/// Draggable gutters of a grid.
+///
+/// A column gutter only runs along the rows that have a cell on both sides of
+/// it, so it stops above a short or spanning last row.
+pub fn grid_gutters(count: usize, rows: usize, cols: usize, col_ratios: &[f32], row_ratios: &[f32], area: Rect) -> Vec<GridGutter> {
+    let count = count.min(rows * cols);
+    if count == 0 {
+        return Vec::new();
+    }
+    let xs = grid_edges(area.pos.x, area.size.x, cols, col_ratios);
+    let ys = grid_edges(area.pos.y, area.size.y, rows, row_ratios);
+    let used_rows = count.div_ceil(cols);
+
+    let mut gutters = Vec::new();
+    for index in 0..cols.saturating_sub(1) {
+        // Rows fill left to right, so the rows with cell `index + 1` come first
+        let column_rows = (0..used_rows).filter(|row| row * cols + index + 1 < count).count();
+        if column_rows == 0 {
+            continue;
+        }
+        let (top, bottom) = (ys[0].0, ys[column_rows - 1].1);
+        gutters.push(GridGutter {
+            axis: GridAxis::Columns,
+            index,
+            rect: Rect { pos: dvec2(xs[index].1, top), size: dvec2(xs[index + 1].0 - xs[index].1, bottom - top) },
+            span: (xs[index].0, xs[index + 1].1),
+        });
+    }
+    for index in 0..used_rows.saturating_sub(1) {
+        gutters.push(GridGutter {
+            axis: GridAxis::Rows,
+            index,
+            rect: Rect { pos: dvec2(area.pos.x, ys[index].1), size: dvec2(area.size.x, ys[index + 1].0 - ys[index].1) },
+            span: (ys[index].0, ys[index + 1].1),
+        });
+    }
+    gutters
+}
+
+/// Ratios with one entry per track, summing to 1 (uniform if they don't fit)
+pub fn normalized_ratios(ratios: &[f32], tracks: usize) -> Vec<f32> {
+    let total: f32 = ratios.iter().sum();
+    if ratios.len() != tracks || total <= 0.0 || ratios.iter().any(|r| *r <= 0.0) {
+        return vec![1.0 / tracks.max(1) as f32; tracks];
+    }
+    ratios.iter().map(|r| r / total).collect()
+}
+
+/// Move the boundary between track `index` and `index + 1`, keeping their combined share
+pub fn set_track_boundary(ratios: &mut Vec<f32>, tracks: usize, index: usize, fraction: f32) {
+    *ratios = normalized_ratios(ratios, tracks);
+    if index + 1 >= tracks {
+        return;
+    }
+    let pair = ratios[index] + ratios[index + 1];
+    let first = (pair * fraction).clamp(MIN_TRACK_SHARE, (pair - MIN_TRACK_SHARE).max(MIN_TRACK_SHARE));
+    ratios[index] = first;
+    ratios[index + 1] = pair - first;
+}
+
+/// Grow (or shrink, with a negative delta) one track; the others give or take proportionally
+pub fn resize_track(ratios: &mut Vec<f32>, tracks: usize, index: usize, delta: f32) -> bool {
+    *ratios = normalized_ratios(ratios, tracks);
+    if tracks < 2 || index >= tracks {
+        return false;
+    }
+    let max_share = 1.0 - MIN_TRACK_SHARE * (tracks - 1) as f32;
+    let old = ratios[index];
+    let new = (old + delta).clamp(MIN_TRACK_SHARE, max_share);
+    let scale = (1.0 - new) / (1.0 - old);
+    // Refuse rather than squeeze another track below the minimum
+    let others_fit = ratios
+        .iter()
+        .enumerate()
+        .all(|(i, r)| i == index || r * scale >= MIN_TRACK_SHARE - f32::EPSILON);
+    if (new - old).abs() < f32::EPSILON || !others_fit {
+        return false;
+    }
+    for (i, ratio) in ratios.iter_mut().enumerate() {
+        *ratio = if i == index { new } else { *ratio * scale };
+    }
+    true
+}
+
+/// Grow (or shrink) the row and column of cell `index` in a grid of `count` cells
+pub fn resize_grid_cell(
+    count: usize,
+    index: usize,
+    rows: usize,
+    cols: usize,
+    col_ratios: &mut Vec<f32>,
+    row_ratios: &mut Vec<f32>,
+    delta: f32,
+) -> bool {
+    if cols == 0 || index >= count {
+        return false;
+    }
+    let (row, col) = (index / cols, index % cols);
+    // A cell alone on the last row spans every column; only its row can change
+    let spans_row = row > 0 && col == 0 && index + 1 == count;
+    let mut changed = resize_track(row_ratios, rows, row, delta);
+    if !spans_row {
+        changed |= resize_track(col_ratios, cols, col, delta);
+    }
+    changed
+}
+
+/// (start, end) of each grid track, with DIVIDER_SIZE gutters between tracks
+pub fn grid_edges(start: f64, length: f64, tracks: usize, ratios: &[f32]) -> Vec<(f64, f64)> {
+    let available = (length - DIVIDER_SIZE * (tracks.saturating_sub(1)) as f64).max(0.0);
+    let weights: Vec<f64> = (0..tracks)
+        .map(|i| ratios.get(i).copied().filter(|r| *r > 0.0).unwrap_or(1.0 / tracks as f32) as f64)
+        .collect();
+    let total: f64 = weights.iter().sum();
+    let mut edges = Vec::with_capacity(tracks);
+    let mut position = start;
+    for (i, weight) in weights.iter().enumerate() {
+        let size = if i + 1 == tracks {
+            // Last track absorbs rounding so the grid ends exactly at the area edge
+            (start + length - position).max(0.0)
+        } else {
+            (available * weight / total).round()
+        };
+        edges.push((position, position + size));
+        position += size + DIVIDER_SIZE;
+    }
+    edges
+}
+
+/// Rectangle of every terminal leaf, in tree order
+pub fn pane_rects(layout: &PanelContent, area: Rect) -> Vec<(u64, Rect)> {
+    let mut rects = Vec::new();
+    collect_panes(layout, area, &mut rects);
+    rects
+}
+
+fn collect_panes(layout: &PanelContent, area: Rect, rects: &mut Vec<(u64, Rect)>) {
+    match layout {
+        PanelContent::Terminal(id) => rects.push((*id, area)),
+        PanelContent::Split { direction, first, second, ratio } => {
+            let (first_rect, second_rect) = split_rect(area, *direction, *ratio);
+            collect_panes(first, first_rect, rects);
+            collect_panes(second, second_rect, rects);
+        }
+    }
+}
+
+/// The pane geometrically nearest to `from` in a direction.
+///
+/// Only panes entirely on that side are candidates; the closest edge wins and
+/// ties go to the pane best aligned with `from` on the other axis.
+pub fn neighbor(panes: &[(u64, Rect)], from: u64, direction: PaneDirection) -> Option<u64> {
+    let (_, origin) = panes.iter().find(|(id, _)| *id == from)?;
+    // Allow for the divider and rounding between adjacent panes
+    let slack = DIVIDER_SIZE + 1.0;
+    panes
+        .iter()
+        .filter(|(id, _)| *id != from)
+        .filter_map(|(id, rect)| {
+            let (gap, origin_span, span) = match direction {
+                PaneDirection::Left => (origin.pos.x - (rect.pos.x + rect.size.x), y_span(origin), y_span(rect)),
+                PaneDirection::Right => (rect.pos.x - (origin.pos.x + origin.size.x), y_span(origin), y_span(rect)),
+                PaneDirection::Up => (origin.pos.y - (rect.pos.y + rect.size.y), x_span(origin), x_span(rect)),
+                PaneDirection::Down => (rect.pos.y - (origin.pos.y + origin.size.y), x_span(origin), x_span(rect)),
+            };
+            if gap < -slack {
+                return None;
+            }
+            // Distance between the spans on the other axis (0 when they overlap)
+            let misalignment = (span.0 - origin_span.1).max(origin_span.0 - span.1).max(0.0);
+            let center_offset = ((span.0 + span.1) - (origin_span.0 + origin_span.1)).abs() / 2.0;
+            Some((*id, (gap.max(0.0), misalignment, center_offset)))
+        })
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(id, _)| id)
+}
+
+fn x_span(rect: &Rect) -> (f64, f64) {
+    (rect.pos.x, rect.pos.x + rect.size.x)
+}
+
+fn y_span(rect: &Rect) -> (f64, f64) {
+    (rect.pos.y, rect.pos.y + rect.size.y)
+}
+
+/// Every divider in the tree, outermost first
+pub fn dividers(layout: &PanelContent, area: Rect) -> Vec<Divider> {
+    let mut dividers = Vec::new();
+    collect_dividers(layout, area, &mut Vec::new(), &mut dividers);
+    dividers
+}
+
+fn collect_dividers(layout: &PanelContent, area: Rect, path: &mut Vec<usize>, dividers: &mut Vec<Divider>) {
+    let PanelContent::Split { direction, first, second, ratio } = layout else {
+        return;
+    };
+    let (first_rect, second_rect) = split_rect(area, *direction, *ratio);
+    let rect = match direction {
+        SplitDirection::Vertical => Rect {
+            pos: dvec2(first_rect.pos.x + first_rect.size.x, area.pos.y),
+            size: dvec2(DIVIDER_SIZE, area.size.y),
+        },
+        SplitDirection::Horizontal => Rect {
+            pos: dvec2(area.pos.x, first_rect.pos.y + first_rect.size.y),
+            size: dvec2(area.size.x, DIVIDER_SIZE),
+        },
+    };
+    dividers.push(Divider { path: path.clone(), direction: *direction, rect, parent: area });
+
+    path.push(0);
+    collect_dividers(first, first_rect, path, dividers);
+    path.pop();
+    path.push(1);
+    collect_dividers(second, second_rect, path, dividers);
+    path.pop();
+}
+
+/// Split a rectangle in two, leaving DIVIDER_SIZE between the halves
+pub fn split_rect(area: Rect, direction: SplitDirection, ratio: f32) -> (Rect, Rect) {
+    let ratio = ratio.clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO) as f64;
+    match direction {
+        SplitDirection::Vertical => {
+            let available = (area.size.x - DIVIDER_SIZE).max(0.0);
+            let first_width = (available * ratio).round();
+            (
+                Rect { pos: area.pos, size: dvec2(first_width, area.size.y) },
+                Rect {
+                    pos: dvec2(area.pos.x + first_width + DIVIDER_SIZE, area.pos.y),
+                    size: dvec2(available - first_width, area.size.y),
+                },
+            )
+        }
+        SplitDirection::Horizontal => {
+            let available = (area.size.y - DIVIDER_SIZE).max(0.0);
+            let first_height = (available * ratio).round();
+            (
+                Rect { pos: area.pos, size: dvec2(area.size.x, first_height) },
+                Rect {
+                    pos: dvec2(area.pos.x, area.pos.y + first_height + DIVIDER_SIZE),
+                    size: dvec2(area.size.x, available - first_height),
+                },
+            )
+        }
+    }
+}
+
+/// Size of the terminal grid area inside a pane, at least one cell
+pub fn grid_size(pane: Rect, cell_size: Size) -> Size {
+    Size {
+        width: ((pane.size.x - PANE_PADDING) as f32).max(cell_size.width),
+        height: ((pane.size.y - PANE_PADDING) as f32).max(cell_size.height),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> Rect {
+        Rect { pos: dvec2(x, y), size: dvec2(w, h) }
+    }
+
+    fn leaf(id: u64) -> PanelContent {
+        PanelContent::Terminal(id)
+    }
+
+    fn split(direction: SplitDirection, first: PanelContent, second: PanelContent, ratio: f32) -> PanelContent {
+        PanelContent::Split { direction, first: Box::new(first), second: Box::new(second), ratio }
+    }
+
+    /// 1 | (2 / 3) in a 404x404 area: every pane edge lands on whole pixels
+    fn l_shape() -> PanelContent {
+        split(SplitDirection::Vertical, leaf(1), split(SplitDirection::Horizontal, leaf(2), leaf(3), 0.5), 0.5)
+    }
+
+    fn assert_ratios(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} vs {:?}", actual, expected);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-5, "{:?} vs {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn resize_track_takes_from_the_others_proportionally() {
+        let mut ratios = vec![0.5, 0.25, 0.25];
+        assert!(resize_track(&mut ratios, 3, 0, 0.1));
+        assert_ratios(&ratios, &[0.6, 0.2, 0.2]);
+        assert!(resize_track(&mut ratios, 3, 1, -0.1));
+        assert_ratios(&ratios, &[0.675, 0.1, 0.225]);
+
+        // Missing or stale ratios start from a uniform split
+        let mut ratios = Vec::new();
+        assert!(resize_track(&mut ratios, 2, 1, 0.1));
+        assert_ratios(&ratios, &[0.4, 0.6]);
+    }
+
+    #[test]
+    fn resize_track_limits() {
+        // The grown track stops where the others reach the minimum share
+        let mut ratios = vec![0.5, 0.5];
+        assert!(resize_track(&mut ratios, 2, 0, 1.0));
+        assert_ratios(&ratios, &[1.0 - MIN_TRACK_SHARE, MIN_TRACK_SHARE]);
+        assert!(!resize_track(&mut ratios, 2, 0, 0.1));
+
+        // Shrinking stops at the minimum
+        let mut ratios = vec![0.5, 0.5];
+        assert!(resize_track(&mut ratios, 2, 1, -1.0));
+        assert_ratios(&ratios, &[1.0 - MIN_TRACK_SHARE, MIN_TRACK_SHARE]);
+        assert!(!resize_track(&mut ratios, 2, 1, -0.1));
+
+        // Refused when another track would go below the minimum
+        let mut ratios = vec![0.5, 0.45, 0.05];
+        assert!(!resize_track(&mut ratios, 3, 0, 0.1));
+        assert_ratios(&ratios, &[0.5, 0.45, 0.05]);
+
+        // Nothing to trade with a single track or a bad index
+        assert!(!resize_track(&mut vec![1.0], 1, 0, 0.1));
+        assert!(!resize_track(&mut vec![0.5, 0.5], 2, 2, 0.1));
+    }
+
+    #[test]
+    fn resize_grid_cell_moves_its_row_and_column() {
+        // 2x2 grid, cell 3 is bottom-right
+        let (mut cols, mut rows) = (vec![0.5, 0.5], vec![0.5, 0.5]);
+        assert!(resize_grid_cell(4, 3, 2, 2, &mut cols, &mut rows, 0.1));
+        assert_ratios(&cols, &[0.4, 0.6]);
+        assert_ratios(&rows, &[0.4, 0.6]);
+
+        // 3 tabs: the last one spans both columns, so only its row grows
+        let (mut cols, mut rows) = (vec![0.5, 0.5], vec![0.5, 0.5]);
+        assert!(resize_grid_cell(3, 2, 2, 2, &mut cols, &mut rows, 0.1));
+        assert_ratios(&cols, &[0.5, 0.5]);
+        assert_ratios(&rows, &[0.4, 0.6]);
+
+        // A single-row grid still resizes the column
+        let (mut cols, mut rows) = (vec![0.5, 0.5], vec![1.0]);
+        assert!(resize_grid_cell(2, 0, 1, 2, &mut cols, &mut rows, -0.1));
+        assert_ratios(&cols, &[0.4, 0.6]);
+
+        assert!(!resize_grid_cell(2, 5, 1, 2, &mut cols, &mut rows, 0.1));
+    }
+
+    #[test]
+    fn normalized_ratios_fall_back_to_uniform() {
+        assert_ratios(&normalized_ratios(&[2.0, 6.0], 2), &[0.25, 0.75]);
+        assert_ratios(&normalized_ratios(&[0.5, 0.5], 3), &[1.0 / 3.0; 3]);
+        assert_ratios(&normalized_ratios(&[0.5, 0.0], 2), &[0.5, 0.5]);
+        assert_ratios(&normalized_ratios(&[-1.0, 2.0], 2), &[0.5, 0.5]);
+        assert!(normalized_ratios(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn set_track_boundary_keeps_the_pair_share() {
+        let mut ratios = vec![0.5, 0.25, 0.25];
+        set_track_boundary(&mut ratios, 3, 1, 0.2);
+        assert_ratios(&ratios, &[0.5, 0.1, 0.4]);
+        set_track_boundary(&mut ratios, 3, 1, 0.0);
+        assert_ratios(&ratios, &[0.5, MIN_TRACK_SHARE, 0.5 - MIN_TRACK_SHARE]);
+        set_track_boundary(&mut ratios, 3, 0, 1.0);
+        assert_ratios(&ratios, &[0.55 - MIN_TRACK_SHARE, MIN_TRACK_SHARE, 0.45]);
+        // The last track has no boundary after it
+        set_track_boundary(&mut ratios, 3, 2, 0.5);
+        assert_ratios(&ratios, &[0.55 - MIN_TRACK_SHARE, MIN_TRACK_SHARE, 0.45]);
+    }
+
+    #[test]
+    fn grid_cells_fill_rows_and_span_a_lone_last_cell() {
+        let area = rect(0.0, 0.0, 404.0, 404.0);
+        assert_eq!(
+            grid_cells(3, 2, 2, &[], &[], area),
+            vec![rect(0.0, 0.0, 200.0, 200.0), rect(204.0, 0.0, 200.0, 200.0), rect(0.0, 204.0, 404.0, 200.0)]
+        );
+        assert_eq!(grid_cells(4, 2, 2, &[], &[], area)[3], rect(204.0, 204.0, 200.0, 200.0));
+        // Tabs past rows x cols get no cell
+        assert_eq!(grid_cells(6, 2, 2, &[], &[], area).len(), 4);
+        assert_eq!(
+            grid_cells(2, 1, 2, &[0.25, 0.75], &[], area),
+            vec![rect(0.0, 0.0, 100.0, 404.0), rect(104.0, 0.0, 300.0, 404.0)]
+        );
+        assert!(grid_cells(2, 0, 2, &[], &[], area).is_empty());
+    }
+
+    #[test]
+    fn column_gutters_stop_at_missing_cells() {
+        // 3 tabs in 2x2: the column gutter only runs along the first row
+        let gutters = grid_gutters(3, 2, 2, &[], &[], rect(0.0, 0.0, 404.0, 404.0));
+        assert_eq!(gutters.len(), 2);
+        assert_eq!((gutters[0].axis, gutters[0].index), (GridAxis::Columns, 0));
+        assert_eq!(gutters[0].rect, rect(200.0, 0.0, 4.0, 200.0));
+        assert_eq!((gutters[1].axis, gutters[1].index), (GridAxis::Rows, 0));
+        assert_eq!(gutters[1].rect, rect(0.0, 200.0, 404.0, 4.0));
+        assert_eq!(gutters[1].span, (0.0, 404.0));
+
+        // 5 tabs in 2x3: the second column gutter has no cell to its right on row 2
+        let gutters = grid_gutters(5, 2, 3, &[], &[], rect(0.0, 0.0, 608.0, 404.0));
+        let columns: Vec<Rect> = gutters.iter().filter(|g| g.axis == GridAxis::Columns).map(|g| g.rect).collect();
+        assert_eq!(columns, vec![rect(200.0, 0.0, 4.0, 404.0), rect(404.0, 0.0, 4.0, 200.0)]);
+
+        // 4 tabs in 2x3: the lone last tab spans row 2, so no column gutter reaches it
+        let gutters = grid_gutters(4, 2, 3, &[], &[], rect(0.0, 0.0, 608.0, 404.0));
+        let columns: Vec<Rect> = gutters.iter().filter(|g| g.axis == GridAxis::Columns).map(|g| g.rect).collect();
+        assert_eq!(columns, vec![rect(200.0, 0.0, 4.0, 200.0), rect(404.0, 0.0, 4.0, 200.0)]);
+
+        assert!(grid_gutters(0, 2, 2, &[], &[], rect(0.0, 0.0, 404.0, 404.0)).is_empty());
+    }
+
+    #[test]
+    fn gutter_fraction_follows_the_pointer() {
+        let gutters = grid_gutters(2, 1, 2, &[], &[], rect(0.0, 0.0, 404.0, 404.0));
+        assert_eq!(gutters[0].fraction_at(dvec2(102.0, 0.0)), 0.25);
+        assert_eq!(gutters[0].fraction_at(dvec2(500.0, 0.0)), MAX_SPLIT_RATIO);
+    }
+
+    #[test]
+    fn split_rect_leaves_room_for_the_divider() {
+        let area = rect(10.0, 20.0, 404.0, 104.0);
+        assert_eq!(
+            split_rect(area, SplitDirection::Vertical, 0.25),
+            (rect(10.0, 20.0, 100.0, 104.0), rect(114.0, 20.0, 300.0, 104.0))
+        );
+        assert_eq!(
+            split_rect(area, SplitDirection::Horizontal, 0.5),
+            (rect(10.0, 20.0, 404.0, 50.0), rect(10.0, 74.0, 404.0, 50.0))
+        );
+        // Out-of-range ratios are clamped
+        assert_eq!(split_rect(area, SplitDirection::Vertical, 0.0).0.size.x, 40.0);
+    }
+
+    #[test]
+    fn pane_rects_and_dividers_of_an_l_shape() {
+        let area = rect(0.0, 0.0, 404.0, 404.0);
+        assert_eq!(
+            pane_rects(&l_shape(), area),
+            vec![
+                (1, rect(0.0, 0.0, 200.0, 404.0)),
+                (2, rect(204.0, 0.0, 200.0, 200.0)),
+                (3, rect(204.0, 204.0, 200.0, 200.0)),
+            ]
+        );
+
+        let dividers = dividers(&l_shape(), area);
+        assert_eq!(dividers.len(), 2);
+        assert_eq!(dividers[0].path, Vec::<usize>::new());
+        assert_eq!(dividers[0].rect, rect(200.0, 0.0, 4.0, 404.0));
+        assert_eq!(dividers[1].path, vec![1]);
+        assert_eq!(dividers[1].rect, rect(204.0, 200.0, 200.0, 4.0));
+        assert_eq!(dividers[1].parent, rect(204.0, 0.0, 200.0, 404.0));
+
+        assert!(dividers[0].grabs(dvec2(198.0, 50.0)));
+        assert!(!dividers[0].grabs(dvec2(190.0, 50.0)));
+        assert_eq!(dividers[0].ratio_at(dvec2(102.0, 0.0)), 0.25);
+        assert_eq!(dividers[0].ratio_at(dvec2(0.0, 0.0)), MIN_SPLIT_RATIO);
+    }
+
+    #[test]
+    fn neighbors_in_an_l_shape() {
+        let panes = pane_rects(&l_shape(), rect(0.0, 0.0, 404.0, 404.0));
+        let table = [
+            (2, PaneDirection::Left, Some(1)),
+            (3, PaneDirection::Left, Some(1)),
+            (2, PaneDirection::Down, Some(3)),
+            (3, PaneDirection::Up, Some(2)),
+            (2, PaneDirection::Up, None),
+            (2, PaneDirection::Right, None),
+            (1, PaneDirection::Left, None),
+            (1, PaneDirection::Up, None),
+            (9, PaneDirection::Left, None),
+        ];
+        for (from, direction, expected) in table {
+            assert_eq!(neighbor(&panes, from, direction), expected, "{} {:?}", from, direction);
+        }
+        // Both right-hand panes touch 1; either is a valid answer
+        assert!(matches!(neighbor(&panes, 1, PaneDirection::Right), Some(2 | 3)));
+    }
+
+    #[test]
+    fn neighbors_in_staggered_splits() {
+        // (1 / 2) | (3 / 4) with the left divider high and the right one low
+        let layout = split(
+            SplitDirection::Vertical,
+            split(SplitDirection::Horizontal, leaf(1), leaf(2), 0.25),
+            split(SplitDirection::Horizontal, leaf(3), leaf(4), 0.75),
+            0.5,
+        );
+        let panes = pane_rects(&layout, rect(0.0, 0.0, 404.0, 404.0));
+        // When several panes touch, the one whose center is closest wins
+        let table = [
+            (1, PaneDirection::Right, Some(3)),
+            (2, PaneDirection::Right, Some(4)),
+            (4, PaneDirection::Left, Some(2)),
+            (3, PaneDirection::Left, Some(1)),
+            (1, PaneDirection::Down, Some(2)),
+            (4, PaneDirection::Up, Some(3)),
+        ];
+        for (from, direction, expected) in table {
+            assert_eq!(neighbor(&panes, from, direction), expected, "{} {:?}", from, direction);
+        }
+    }
+}
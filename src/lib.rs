@@ -1,4 +1,16 @@
 pub use makepad_widgets;
 pub mod app;
+pub mod clipboard;
+pub mod config;
+pub mod input;
+pub mod keymap;
+pub mod layout;
+pub mod mouse;
+pub mod process;
+pub mod pty_tap;
+pub mod tab_strip;
 pub mod types;
 pub mod terminal;
+pub mod terminal_panes;
+pub mod terminal_view;
+pub mod theme;
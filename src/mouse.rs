@@ -0,0 +1,185 @@
+// ModernTerm - Mouse Reporting
+// Encodes mouse events for terminal applications (xterm mouse tracking modes)
+
+use alacritty_terminal::index::{Column, Point};
+use alacritty_terminal::term::TermMode;
+use makepad_widgets::KeyModifiers;
+
+/// Mouse button as seen by the terminal application
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    WheelUp,
+    WheelDown,
+    /// Motion without any button held (any-event tracking)
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    Press,
+    Release,
+    Motion,
+}
+
+/// One mouse event in viewport cell coordinates (0-based)
+#[derive(Debug, Clone, Copy)]
+pub struct MouseReport {
+    pub button: MouseButton,
+    pub action: MouseAction,
+    pub point: Point<usize, Column>,
+    pub modifiers: KeyModifiers,
+}
+
+/// Whether the program asked for any kind of mouse tracking (1000/1002/1003)
+pub fn is_reporting(mode: TermMode) -> bool {
+    mode.intersects(TermMode::MOUSE_MODE)
+}
+
+/// Encode a mouse event for the PTY, or None if the current mode doesn't want it.
+///
+/// Uses SGR (1006) when enabled, otherwise the legacy X10-style byte encoding,
+/// optionally with UTF-8 (1005) extended coordinates.
+pub fn encode(report: &MouseReport, mode: TermMode) -> Option<Vec<u8>> {
+    if !wants(report, mode) {
+        return None;
+    }
+
+    let mut code = match report.button {
+        MouseButton::Left => 0,
+        MouseButton::Middle => 1,
+        MouseButton::Right => 2,
+        MouseButton::None => 3,
+        MouseButton::WheelUp => 64,
+        MouseButton::WheelDown => 65,
+    };
+    if report.action == MouseAction::Motion {
+        code += 32;
+    }
+    if report.modifiers.shift {
+        code += 4;
+    }
+    if report.modifiers.alt {
+        code += 8;
+    }
+    if report.modifiers.control {
+        code += 16;
+    }
+
+    let column = report.point.column.0 + 1;
+    let line = report.point.line + 1;
+
+    if mode.contains(TermMode::SGR_MOUSE) {
+        // SGR keeps the button on release and marks it with a lowercase 'm'
+        let suffix = if report.action == MouseAction::Release { 'm' } else { 'M' };
+        return Some(format!("\x1b[<{};{};{}{}", code, column, line, suffix).into_bytes());
+    }
+
+    // Legacy encodings can't tell which button was released
+    if report.action == MouseAction::Release {
+        code = (code & !0b11) | 3;
+    }
+
+    let mut bytes = b"\x1b[M".to_vec();
+    bytes.push(32 + code as u8);
+    if mode.contains(TermMode::UTF8_MOUSE) {
+        encode_utf8_coordinate(&mut bytes, column)?;
+        encode_utf8_coordinate(&mut bytes, line)?;
+    } else {
+        // X10 coordinates are single bytes; xterm drops events past column/line 223
+        bytes.push(u8::try_from(32 + column).ok()?);
+        bytes.push(u8::try_from(32 + line).ok()?);
+    }
+    Some(bytes)
+}
+
+/// Filter events by tracking mode: 1000 clicks, 1002 drags, 1003 all motion
+fn wants(report: &MouseReport, mode: TermMode) -> bool {
+    match report.action {
+        MouseAction::Press | MouseAction::Release => mode.intersects(TermMode::MOUSE_MODE),
+        MouseAction::Motion if report.button == MouseButton::None => mode.contains(TermMode::MOUSE_MOTION),
+        MouseAction::Motion => mode.intersects(TermMode::MOUSE_DRAG | TermMode::MOUSE_MOTION),
+    }
+}
+
+/// UTF-8 (1005) coordinate: value + 32 as a UTF-8 encoded code point, max 2015
+fn encode_utf8_coordinate(bytes: &mut Vec<u8>, value: usize) -> Option<()> {
+    let code_point = u32::try_from(32 + value).ok().filter(|&c| c < 0x800)?;
+    let ch = char::from_u32(code_point)?;
+    let mut buffer = [0u8; 4];
+    bytes.extend_from_slice(ch.encode_utf8(&mut buffer).as_bytes());
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: KeyModifiers = KeyModifiers { shift: false, control: false, alt: false, logo: false };
+    const SHIFT: KeyModifiers = KeyModifiers { shift: true, ..NONE };
+    const CTRL_ALT: KeyModifiers = KeyModifiers { control: true, alt: true, ..NONE };
+
+    /// Zero-based cell (column, line) like the view reports it
+    fn report(button: MouseButton, action: MouseAction, column: usize, line: usize, modifiers: KeyModifiers) -> MouseReport {
+        MouseReport { button, action, point: Point::new(line, Column(column)), modifiers }
+    }
+
+    #[test]
+    fn sgr_reference_table() {
+        let clicks = TermMode::MOUSE_REPORT_CLICK | TermMode::SGR_MOUSE;
+        let drags = TermMode::MOUSE_DRAG | TermMode::SGR_MOUSE;
+        let motion = TermMode::MOUSE_MOTION | TermMode::SGR_MOUSE;
+        let table: Vec<(MouseReport, TermMode, Option<&[u8]>)> = vec![
+            (report(MouseButton::Left, MouseAction::Press, 0, 0, NONE), clicks, Some(b"\x1b[<0;1;1M")),
+            (report(MouseButton::Left, MouseAction::Release, 0, 0, NONE), clicks, Some(b"\x1b[<0;1;1m")),
+            (report(MouseButton::Middle, MouseAction::Press, 9, 4, NONE), clicks, Some(b"\x1b[<1;10;5M")),
+            (report(MouseButton::Right, MouseAction::Release, 9, 4, NONE), clicks, Some(b"\x1b[<2;10;5m")),
+            (report(MouseButton::WheelUp, MouseAction::Press, 2, 3, NONE), clicks, Some(b"\x1b[<64;3;4M")),
+            (report(MouseButton::WheelDown, MouseAction::Press, 2, 3, NONE), clicks, Some(b"\x1b[<65;3;4M")),
+            // Modifier bits: shift 4, alt 8, control 16
+            (report(MouseButton::Left, MouseAction::Press, 0, 0, SHIFT), clicks, Some(b"\x1b[<4;1;1M")),
+            (report(MouseButton::Right, MouseAction::Press, 0, 0, CTRL_ALT), clicks, Some(b"\x1b[<26;1;1M")),
+            // Motion adds 32 and depends on the tracking mode
+            (report(MouseButton::Left, MouseAction::Motion, 5, 6, NONE), clicks, None),
+            (report(MouseButton::Left, MouseAction::Motion, 5, 6, NONE), drags, Some(b"\x1b[<32;6;7M")),
+            (report(MouseButton::None, MouseAction::Motion, 5, 6, NONE), drags, None),
+            (report(MouseButton::None, MouseAction::Motion, 5, 6, NONE), motion, Some(b"\x1b[<35;6;7M")),
+            // SGR has no coordinate limit
+            (report(MouseButton::Left, MouseAction::Press, 2999, 499, NONE), clicks, Some(b"\x1b[<0;3000;500M")),
+            // No tracking at all
+            (report(MouseButton::Left, MouseAction::Press, 0, 0, NONE), TermMode::SGR_MOUSE, None),
+        ];
+        for (report, mode, expected) in table {
+            assert_eq!(encode(&report, mode).as_deref(), expected, "{:?} in {:?}", report, mode);
+        }
+    }
+
+    #[test]
+    fn legacy_reference_table() {
+        let clicks = TermMode::MOUSE_REPORT_CLICK;
+        let utf8 = TermMode::MOUSE_REPORT_CLICK | TermMode::UTF8_MOUSE;
+        let drags = TermMode::MOUSE_DRAG;
+        let table: Vec<(MouseReport, TermMode, Option<&[u8]>)> = vec![
+            (report(MouseButton::Left, MouseAction::Press, 0, 0, NONE), clicks, Some(b"\x1b[M !!")),
+            (report(MouseButton::Right, MouseAction::Press, 9, 4, SHIFT), clicks, Some(b"\x1b[M&*%")),
+            // Release is button 3 whichever button it was, modifiers kept
+            (report(MouseButton::Right, MouseAction::Release, 0, 0, SHIFT), clicks, Some(b"\x1b[M'!!")),
+            (report(MouseButton::WheelDown, MouseAction::Press, 0, 0, NONE), clicks, Some(b"\x1b[Ma!!")),
+            (report(MouseButton::Left, MouseAction::Motion, 1, 1, NONE), drags, Some(b"\x1b[M@\"\"")),
+            // X10 coordinates stop at 223
+            (report(MouseButton::Left, MouseAction::Press, 222, 0, NONE), clicks, Some(b"\x1b[M \xff!")),
+            (report(MouseButton::Left, MouseAction::Press, 223, 0, NONE), clicks, None),
+            (report(MouseButton::Left, MouseAction::Press, 0, 223, NONE), clicks, None),
+            // UTF-8 coordinates go up to 2015
+            (report(MouseButton::Left, MouseAction::Press, 0, 0, NONE), utf8, Some(b"\x1b[M !!")),
+            (report(MouseButton::Left, MouseAction::Press, 223, 0, NONE), utf8, Some(b"\x1b[M \xc4\x80!")),
+            (report(MouseButton::Left, MouseAction::Press, 2014, 0, NONE), utf8, Some(b"\x1b[M \xdf\xbf!")),
+            (report(MouseButton::Left, MouseAction::Press, 2015, 0, NONE), utf8, None),
+        ];
+        for (report, mode, expected) in table {
+            assert_eq!(encode(&report, mode).as_deref(), expected, "{:?} in {:?}", report, mode);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// ModernTerm - Process Info
+// Foreground process (name, cwd) of a shell's PTY, read from /proc on Linux (None elsewhere)
+
+/// Fields of /proc/<pid>/stat that we use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcStat {
+    /// Executable name (truncated to 15 bytes by the kernel)
+    pub comm: String,
+    /// Foreground process group of the process's controlling terminal
+    pub tpgid: i32,
+}
+
+/// Parse a /proc/<pid>/stat line.
+///
+/// The name sits in parentheses and may itself contain spaces or `)`, so the
+/// numeric fields are counted from the last `)`.
+pub fn parse_stat(stat: &str) -> Option<ProcStat> {
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let comm = stat.get(open + 1..close)?.to_string();
+    // state ppid pgrp session tty_nr tpgid ...
+    let tpgid = stat.get(close + 1..)?.split_whitespace().nth(5)?.parse().ok()?;
+    Some(ProcStat { comm, tpgid })
+}
+
+#[cfg(target_os = "linux")]
+fn read_stat(pid: u32) -> Option<ProcStat> {
+    parse_stat(&std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?)
+}
+
+/// Leader of the foreground process group on the shell's terminal (the shell
+/// itself at the prompt, `vim` while it runs, ...)
+#[cfg(target_os = "linux")]
+pub fn foreground_pid(shell_pid: u32) -> Option<u32> {
+    u32::try_from(read_stat(shell_pid)?.tpgid).ok().filter(|&pid| pid > 0)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn foreground_pid(_shell_pid: u32) -> Option<u32> {
+    None
+}
+
+/// Name of the program running in the foreground of the shell's terminal
+#[cfg(target_os = "linux")]
+pub fn foreground_process_name(shell_pid: u32) -> Option<String> {
+    let stat = read_stat(foreground_pid(shell_pid)?)?;
+    Some(stat.comm).filter(|name| !name.is_empty())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn foreground_process_name(_shell_pid: u32) -> Option<String> {
+    None
+}
+
+/// Working directory of the program in the foreground of the shell's terminal
+#[cfg(target_os = "linux")]
+pub fn foreground_cwd(shell_pid: u32) -> Option<std::path::PathBuf> {
+    std::fs::read_link(format!("/proc/{}/cwd", foreground_pid(shell_pid)?)).ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn foreground_cwd(_shell_pid: u32) -> Option<std::path::PathBuf> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stat_names_with_spaces_and_parens() {
+        let stat = "4242 (tmux: server) S 1 4242 4242 34816 5120 4194560 0";
+        assert_eq!(parse_stat(stat), Some(ProcStat { comm: "tmux: server".into(), tpgid: 5120 }));
+
+        let stat = "77 (a) b) R 1 77 77 0 -1 4194560";
+        assert_eq!(parse_stat(stat), Some(ProcStat { comm: "a) b".into(), tpgid: -1 }));
+
+        assert_eq!(parse_stat("garbage"), None);
+    }
+}
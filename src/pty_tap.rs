@@ -0,0 +1,188 @@
+// ModernTerm - PTY Tap
+// Scans PTY output for OSC 7 (working directory), which alacritty's parser ignores
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Shared slot the tap writes the latest OSC 7 directory into
+pub type ReportedCwd = Arc<Mutex<Option<PathBuf>>>;
+
+/// Longest OSC 7 payload we keep; anything longer is not a path we'd use
+const MAX_PAYLOAD: usize = 4096;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    /// Inside `ESC ]`, collecting while the payload can still be `7;...`
+    Osc,
+    /// ESC inside the OSC, possibly the start of the ST terminator
+    OscEscape,
+}
+
+/// Incremental OSC 7 finder; sequences may be split across reads
+#[derive(Debug, Default)]
+pub struct Osc7Scanner {
+    state: ScanState,
+    payload: Vec<u8>,
+}
+
+impl Osc7Scanner {
+    /// Feed PTY output; returns the last directory reported in it
+    pub fn feed(&mut self, bytes: &[u8]) -> Option<PathBuf> {
+        let mut found = None;
+        for &byte in bytes {
+            if let Some(path) = self.advance(byte) {
+                found = Some(path);
+            }
+        }
+        found
+    }
+
+    fn advance(&mut self, byte: u8) -> Option<PathBuf> {
+        match (self.state, byte) {
+            (ScanState::Ground, 0x1b) => self.state = ScanState::Escape,
+            (ScanState::Ground, _) => {}
+            (ScanState::Escape | ScanState::OscEscape, b']') => {
+                self.payload.clear();
+                self.state = ScanState::Osc;
+            }
+            (ScanState::Escape, 0x1b) => {}
+            (ScanState::Escape, _) => self.state = ScanState::Ground,
+            (ScanState::OscEscape, b'\\') => return self.finish(),
+            (ScanState::OscEscape, 0x1b) => self.state = ScanState::Escape,
+            (ScanState::OscEscape, _) => self.state = ScanState::Ground,
+            (ScanState::Osc, 0x07) => return self.finish(),
+            (ScanState::Osc, 0x1b) => self.state = ScanState::OscEscape,
+            (ScanState::Osc, _) => {
+                self.payload.push(byte);
+                // Titles and every other OSC are dropped as soon as they can't be OSC 7
+                let prefix = &b"7;"[..self.payload.len().min(2)];
+                if !self.payload.starts_with(prefix) || self.payload.len() > MAX_PAYLOAD {
+                    self.state = ScanState::Ground;
+                }
+            }
+        }
+        None
+    }
+
+    fn finish(&mut self) -> Option<PathBuf> {
+        self.state = ScanState::Ground;
+        let uri = std::str::from_utf8(self.payload.strip_prefix(b"7;")?).ok()?;
+        parse_file_uri(uri)
+    }
+}
+
+/// Path of a `file://host/path` URI (as sent by OSC 7), percent-decoded
+pub fn parse_file_uri(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://").or_else(|| uri.strip_prefix("kitty-shell-cwd://"))?;
+    let path = &rest[rest.find('/')?..];
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(bytes).ok()?))
+}
+
+#[cfg(unix)]
+pub use unix::TappedPty;
+
+#[cfg(unix)]
+mod unix {
+    use super::{Osc7Scanner, ReportedCwd};
+    use alacritty_terminal::event::{OnResize, WindowSize};
+    use alacritty_terminal::tty::{ChildEvent, EventedPty, EventedReadWrite, Pty};
+    use polling::{Event, PollMode, Poller};
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::sync::Arc;
+
+    /// alacritty's Pty with reads going through an OSC 7 scanner
+    pub struct TappedPty {
+        pty: Pty,
+        reader: TapReader,
+    }
+
+    pub struct TapReader {
+        // Duplicate of the PTY master fd; shares its non-blocking flag
+        file: File,
+        scanner: Osc7Scanner,
+        cwd: ReportedCwd,
+    }
+
+    impl TappedPty {
+        pub fn new(pty: Pty, cwd: ReportedCwd) -> io::Result<Self> {
+            let file = pty.file().try_clone()?;
+            Ok(Self { pty, reader: TapReader { file, scanner: Osc7Scanner::default(), cwd } })
+        }
+    }
+
+    impl Read for TapReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let read = self.file.read(buf)?;
+            if let Some(path) = self.scanner.feed(&buf[..read]) {
+                *self.cwd.lock().unwrap() = Some(path);
+            }
+            Ok(read)
+        }
+    }
+
+    impl EventedReadWrite for TappedPty {
+        type Reader = TapReader;
+        type Writer = File;
+
+        unsafe fn register(&mut self, poller: &Arc<Poller>, interest: Event, mode: PollMode) -> io::Result<()> {
+            unsafe { self.pty.register(poller, interest, mode) }
+        }
+
+        fn reregister(&mut self, poller: &Arc<Poller>, interest: Event, mode: PollMode) -> io::Result<()> {
+            self.pty.reregister(poller, interest, mode)
+        }
+
+        fn deregister(&mut self, poller: &Arc<Poller>) -> io::Result<()> {
+            self.pty.deregister(poller)
+        }
+
+        fn reader(&mut self) -> &mut TapReader {
+            &mut self.reader
+        }
+
+        fn writer(&mut self) -> &mut File {
+            self.pty.writer()
+        }
+    }
+
+    impl EventedPty for TappedPty {
+        fn next_child_event(&mut self) -> Option<ChildEvent> {
+            self.pty.next_child_event()
+        }
+    }
+
+    impl OnResize for TappedPty {
+        fn on_resize(&mut self, window_size: WindowSize) {
+            self.pty.on_resize(window_size)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osc7_across_reads_and_terminators() {
+        let mut scanner = Osc7Scanner::default();
+        assert_eq!(scanner.feed(b"\x1b]0;title\x07prompt\x1b]7;file://host/ho"), None);
+        assert_eq!(scanner.feed(b"me/me/My%20Dir\x1b\\$ "), Some(PathBuf::from("/home/me/My Dir")));
+        assert_eq!(scanner.feed(b"\x1b]7;file:///tmp\x07"), Some(PathBuf::from("/tmp")));
+        assert_eq!(scanner.feed(b"\x1b]77;file:///nope\x07"), None);
+        assert_eq!(parse_file_uri("http://host/x"), None);
+    }
+}
@@ -0,0 +1,325 @@
+// ModernTerm - Tab Strip Widget
+// One tab per tab_order entry: click to activate, drag to reorder, × or middle-click to close,
+// double-click to rename
+
+use crate::types::{AppState, ViewMode};
+use makepad_widgets::*;
+use unicode_width::UnicodeWidthChar;
+
+live_design!{
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::terminal_view::D2_CODING_FONT;
+
+    pub TabStripBase = {{TabStrip}} {}
+    pub TabStrip = <TabStripBase> {
+        width: Fill,
+        height: Fill,
+
+        draw_tab: { color: #505050 }
+        draw_active_tab: { color: #707070 }
+        draw_hover: { color: #c04040 }
+        draw_text: {
+            text_style: <D2_CODING_FONT> { font_size: 10.0 }
+        }
+        text_color: #c8c8c8
+        active_text_color: #ffffff
+    }
+}
+
+/// Narrowest a tab gets before the strip starts scrolling
+const MIN_TAB_WIDTH: f64 = 100.0;
+/// Widest a tab gets when there is room to spare
+const MAX_TAB_WIDTH: f64 = 200.0;
+const TAB_GAP: f64 = 4.0;
+const TAB_PADDING: f64 = 10.0;
+/// Square hit area of the close button at a tab's right end
+const CLOSE_SIZE: f64 = 16.0;
+/// The ‹ and › buttons shown while tabs overflow
+const SCROLL_BUTTON_WIDTH: f64 = 20.0;
+
+/// Things the tab strip asks the app to do
+#[derive(Clone, Debug, DefaultNone)]
+pub enum TabStripAction {
+    /// Make a tab the active one (click)
+    Activate(u64),
+    /// Close a tab (close button or middle click)
+    Close(u64),
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct TabStrip {
+    #[walk] walk: Walk,
+
+    #[live] draw_tab: DrawColor,
+    #[live] draw_active_tab: DrawColor,
+    #[live] draw_hover: DrawColor,
+    #[live] draw_text: DrawText,
+    #[live] text_color: Vec4,
+    #[live] active_text_color: Vec4,
+
+    #[redraw] #[rust] area: Area,
+    // Index in tab_order of the leftmost visible tab while tabs overflow
+    #[rust] first_visible: usize,
+    // Active tab at the last draw; a new one is scrolled into view
+    #[rust] last_active: Option<u64>,
+    // Tab under the mouse, and whether it is over that tab's close button
+    #[rust] hovered: Option<(u64, bool)>,
+    // Tab being dragged to a new place
+    #[rust] drag: Option<u64>,
+}
+
+impl Widget for TabStrip {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        let Some(state) = scope.data.get::<AppState>() else {
+            return DrawStep::done();
+        };
+
+        let active_index = state.tab_order.iter().position(|&id| id == state.active_tab_id);
+        let strip = StripLayout::new(state.tab_order.len(), self.first_visible, rect);
+        self.first_visible = strip.first_visible;
+        if self.last_active != Some(state.active_tab_id) {
+            self.last_active = Some(state.active_tab_id);
+            if let Some(index) = active_index {
+                self.first_visible = strip.scrolled_to(index);
+            }
+        }
+        let strip = StripLayout::new(state.tab_order.len(), self.first_visible, rect);
+
+        let font_size = self.draw_text.text_style.font_size as f64;
+        let char_width = (font_size * 2.0 / 3.0).round().max(1.0);
+        let text_y = rect.pos.y + (rect.size.y - (font_size * 4.0 / 3.0).round()) / 2.0;
+
+        for &(index, tab_rect) in &strip.tabs {
+            let tab_id = state.tab_order[index];
+            let active = Some(index) == active_index;
+            if active {
+                self.draw_active_tab.draw_abs(cx, tab_rect);
+            } else {
+                self.draw_tab.draw_abs(cx, tab_rect);
+            }
+
+            let close = close_rect(tab_rect);
+            if self.hovered == Some((tab_id, true)) {
+                self.draw_hover.draw_abs(cx, close);
+            }
+            self.draw_text.color = if active { self.active_text_color } else { self.text_color };
+            let close_x = close.pos.x + (close.size.x - char_width) / 2.0;
+            self.draw_text.draw_abs(cx, dvec2(close_x, text_y), "×");
+
+            let columns = ((close.pos.x - tab_rect.pos.x - TAB_PADDING) / char_width).floor().max(0.0) as usize;
+            let title = match &state.tab_rename {
+                // Keep the end of the name and the caret in view while typing
+                Some((renaming, name)) if *renaming == tab_id => tail(&format!("{}▏", name), columns),
+                _ => match state.tabs.get(&tab_id) {
+                    // A pane in a background tab rang the bell
+                    Some(tab) if tab.bell => elide(&format!("● {}", tab.display_title()), columns),
+                    Some(tab) => elide(tab.display_title(), columns),
+                    None => String::new(),
+                },
+            };
+            self.draw_text.draw_abs(cx, dvec2(tab_rect.pos.x + TAB_PADDING, text_y), &title);
+        }
+
+        for (button, glyph, enabled) in [
+            (strip.scroll_left, "‹", strip.first_visible > 0),
+            (strip.scroll_right, "›", strip.first_visible + strip.tabs.len() < state.tab_order.len()),
+        ] {
+            if let Some(button) = button {
+                self.draw_tab.draw_abs(cx, button);
+                self.draw_text.color = if enabled { self.active_text_color } else { self.text_color * 0.5 };
+                let x = button.pos.x + (button.size.x - char_width) / 2.0;
+                self.draw_text.draw_abs(cx, dvec2(x, text_y), glyph);
+            }
+        }
+
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let Some(state) = scope.data.get_mut::<AppState>() else {
+            return;
+        };
+        let strip = StripLayout::new(state.tab_order.len(), self.first_visible, self.area.rect(cx));
+        let tab_at = |abs: DVec2| {
+            strip
+                .tabs
+                .iter()
+                .find(|(_, rect)| rect.contains(abs))
+                .map(|&(index, rect)| (index, rect))
+        };
+
+        let mut action = None;
+        match event.hits(cx, self.area) {
+            Hit::FingerHoverIn(fh) | Hit::FingerHoverOver(fh) => {
+                let hovered = tab_at(fh.abs).map(|(index, rect)| (state.tab_order[index], close_rect(rect).contains(fh.abs)));
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerHoverOut(_) => {
+                self.hovered = None;
+                self.redraw(cx);
+            }
+            Hit::FingerScroll(fs) => {
+                let delta = if fs.scroll.y.abs() >= fs.scroll.x.abs() { fs.scroll.y } else { fs.scroll.x };
+                if delta != 0.0 {
+                    self.scroll_by(cx, &strip, if delta > 0.0 { 1 } else { -1 });
+                }
+            }
+            Hit::FingerDown(fe) => {
+                if strip.scroll_left.is_some_and(|button| button.contains(fe.abs)) {
+                    self.scroll_by(cx, &strip, -1);
+                } else if strip.scroll_right.is_some_and(|button| button.contains(fe.abs)) {
+                    self.scroll_by(cx, &strip, 1);
+                } else if let Some((index, rect)) = tab_at(fe.abs) {
+                    let tab_id = state.tab_order[index];
+                    let middle = fe.mouse_button().is_some_and(|button| button.is_middle());
+                    if middle || (fe.is_primary_hit() && close_rect(rect).contains(fe.abs)) {
+                        action = Some(TabStripAction::Close(tab_id));
+                    } else if fe.is_primary_hit() && fe.tap_count == 2 {
+                        state.start_tab_rename(tab_id);
+                        self.redraw(cx);
+                    } else if fe.is_primary_hit() {
+                        action = Some(TabStripAction::Activate(tab_id));
+                        self.drag = Some(tab_id);
+                    }
+                }
+            }
+            Hit::FingerMove(fe) => {
+                let Some(tab_id) = self.drag else {
+                    return;
+                };
+                let (Some((target, _)), Some(from)) = (tab_at(fe.abs), state.tab_order.iter().position(|&id| id == tab_id)) else {
+                    return;
+                };
+                if target != from {
+                    state.tab_order.remove(from);
+                    state.tab_order.insert(target, tab_id);
+                    // Grid cells follow tab order
+                    if matches!(state.view_mode, ViewMode::Grid { .. }) {
+                        state.schedule_pane_resize(cx);
+                    }
+                    cx.redraw_all();
+                }
+            }
+            Hit::FingerUp(_) => {
+                self.drag = None;
+            }
+            _ => {}
+        }
+
+        if let Some(action) = action {
+            cx.widget_action(self.widget_uid(), &scope.path, action);
+        }
+    }
+}
+
+impl TabStrip {
+    fn scroll_by(&mut self, cx: &mut Cx, strip: &StripLayout, step: isize) {
+        let first = (strip.first_visible as isize + step).clamp(0, strip.max_first as isize) as usize;
+        if first != self.first_visible {
+            self.first_visible = first;
+            self.redraw(cx);
+        }
+    }
+}
+
+/// Where the visible tabs and the scroll buttons go
+struct StripLayout {
+    /// (index in tab_order, rect) of each tab on screen
+    tabs: Vec<(usize, Rect)>,
+    first_visible: usize,
+    /// Largest useful first_visible (0 when every tab fits)
+    max_first: usize,
+    scroll_left: Option<Rect>,
+    scroll_right: Option<Rect>,
+}
+
+impl StripLayout {
+    fn new(count: usize, first_visible: usize, rect: Rect) -> Self {
+        let mut strip = StripLayout { tabs: Vec::new(), first_visible: 0, max_first: 0, scroll_left: None, scroll_right: None };
+        if count == 0 {
+            return strip;
+        }
+        let tab_rect = |x: f64, width: f64| Rect { pos: dvec2(x, rect.pos.y), size: dvec2(width, rect.size.y) };
+
+        let width = ((rect.size.x + TAB_GAP) / count as f64 - TAB_GAP).clamp(MIN_TAB_WIDTH, MAX_TAB_WIDTH);
+        if count as f64 * (width + TAB_GAP) - TAB_GAP <= rect.size.x {
+            strip.tabs = (0..count).map(|i| (i, tab_rect(rect.pos.x + i as f64 * (width + TAB_GAP), width))).collect();
+            return strip;
+        }
+
+        // Overflow: as many minimum-width tabs as fit between the scroll buttons, stretched to fill
+        let inner = (rect.size.x - 2.0 * (SCROLL_BUTTON_WIDTH + TAB_GAP)).max(0.0);
+        let fit = (((inner + TAB_GAP) / (MIN_TAB_WIDTH + TAB_GAP)).floor() as usize).clamp(1, count);
+        let width = ((inner + TAB_GAP) / fit as f64 - TAB_GAP).max(1.0);
+        strip.max_first = count - fit;
+        strip.first_visible = first_visible.min(strip.max_first);
+        let left = rect.pos.x + SCROLL_BUTTON_WIDTH + TAB_GAP;
+        strip.tabs = (0..fit)
+            .map(|i| (strip.first_visible + i, tab_rect(left + i as f64 * (width + TAB_GAP), width)))
+            .collect();
+        strip.scroll_left = Some(tab_rect(rect.pos.x, SCROLL_BUTTON_WIDTH));
+        strip.scroll_right = Some(tab_rect(rect.pos.x + rect.size.x - SCROLL_BUTTON_WIDTH, SCROLL_BUTTON_WIDTH));
+        strip
+    }
+
+    /// The first_visible that brings tab `index` on screen with the least scrolling
+    fn scrolled_to(&self, index: usize) -> usize {
+        let fit = self.tabs.len().max(1);
+        if index < self.first_visible {
+            index
+        } else if index >= self.first_visible + fit {
+            (index + 1 - fit).min(self.max_first)
+        } else {
+            self.first_visible
+        }
+    }
+}
+
+/// Close button area at the right end of a tab
+fn close_rect(tab: Rect) -> Rect {
+    Rect {
+        pos: dvec2(tab.pos.x + tab.size.x - TAB_PADDING / 2.0 - CLOSE_SIZE, tab.pos.y + (tab.size.y - CLOSE_SIZE) / 2.0),
+        size: dvec2(CLOSE_SIZE, CLOSE_SIZE),
+    }
+}
+
+/// Cut a title to `columns` cells (wide chars count double), ending in … when shortened
+fn elide(title: &str, columns: usize) -> String {
+    let width = |ch: char| ch.width().unwrap_or(0);
+    if title.chars().map(width).sum::<usize>() <= columns {
+        return title.to_string();
+    }
+    let mut used = 0;
+    let mut elided = String::new();
+    for ch in title.chars() {
+        if used + width(ch) + 1 > columns {
+            break;
+        }
+        used += width(ch);
+        elided.push(ch);
+    }
+    elided.push('…');
+    elided
+}
+
+/// The last `columns` cells of a text (wide chars count double)
+fn tail(text: &str, columns: usize) -> String {
+    let mut used = 0;
+    let mut chars: Vec<char> = text
+        .chars()
+        .rev()
+        .take_while(|ch| {
+            used += ch.width().unwrap_or(0);
+            used <= columns
+        })
+        .collect();
+    chars.reverse();
+    chars.into_iter().collect()
+}
@@ -1,15 +1,16 @@
 // ModernTerm - Terminal Backend
 // Based on tterm's successful alacritty_terminal integration
 
-use crate::types::{PtyEvent, Size};
+use crate::types::Size;
 use alacritty_terminal::event::{Event, EventListener, Notify, OnResize, WindowSize};
 use makepad_widgets::SignalToUI;
 use alacritty_terminal::event_loop::{EventLoop, Msg, Notifier};
 use alacritty_terminal::grid::{Dimensions, Scroll};
-use alacritty_terminal::index::{Column, Line};
-use alacritty_terminal::selection::SelectionRange;
+use alacritty_terminal::index::{Column, Line, Side};
+use alacritty_terminal::selection::{Selection, SelectionRange, SelectionType};
 use alacritty_terminal::sync::FairMutex;
-use alacritty_terminal::term::{self, cell::Cell, test::TermSize, Term, TermMode};
+use alacritty_terminal::term::{self, cell::Cell, color::Colors, test::TermSize, Term, TermMode};
+use alacritty_terminal::vte::ansi::CursorStyle;
 use alacritty_terminal::{tty, Grid};
 use alacritty_terminal::index::{Point};
 use std::borrow::Cow;
@@ -17,7 +18,7 @@ use std::collections::HashMap;
 use std::io::Result;
 use std::path::PathBuf;
 use std::sync::mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Terminal backend settings (based on tterm)
 #[derive(Debug, Clone)]
@@ -26,6 +27,7 @@ pub struct BackendSettings {
     pub args: Vec<String>,
     pub working_directory: Option<PathBuf>,
     pub env: HashMap<String, String>,
+    pub scrollback_lines: usize,
 }
 
 impl Default for BackendSettings {
@@ -44,6 +46,7 @@ impl Default for BackendSettings {
             args: vec![],
             working_directory: None,
             env,
+            scrollback_lines: 10000,
         }
     }
 }
@@ -109,6 +112,12 @@ pub enum BackendCommand {
     Write(Vec<u8>),
     Scroll(i32),
     Resize(Size, Size),
+    SetScrollback(usize),
+    /// Start a new selection at a grid point (simple, word, line or block)
+    SelectStart(SelectionType, Point, Side),
+    /// Extend the current selection to a grid point
+    SelectUpdate(Point, Side),
+    ClearSelection,
 }
 
 /// Terminal backend (based on tterm's successful implementation)
@@ -119,6 +128,18 @@ pub struct TerminalBackend {
     size: TerminalSize,
     notifier: Notifier,
     last_content: RenderableContent,
+    config: term::Config,
+    // What the shell was started with, reused to restart it after it exits
+    settings: BackendSettings,
+    // Events for the UI thread, drained by App::handle_pty_events
+    events: Arc<Mutex<Vec<Event>>>,
+    // Title set by the program through OSC 0/2 (None after a reset)
+    title: Option<String>,
+    // Exit code of the shell once it has exited
+    exit_code: Option<i32>,
+    exited: bool,
+    // Directory the shell reported through OSC 7
+    reported_cwd: crate::pty_tap::ReportedCwd,
 }
 
 impl TerminalBackend {
@@ -128,18 +149,26 @@ impl TerminalBackend {
         settings: BackendSettings,
     ) -> Result<Self> {
         let pty_config = tty::Options {
-            shell: Some(tty::Shell::new(settings.shell, settings.args)),
-            working_directory: settings.working_directory,
-            env: settings.env,
+            shell: Some(tty::Shell::new(settings.shell.clone(), settings.args.clone())),
+            working_directory: settings.working_directory.clone(),
+            env: settings.env.clone(),
             ..tty::Options::default()
         };
-        let config = term::Config::default();
+        let config = term::Config {
+            scrolling_history: settings.scrollback_lines,
+            // Honor `CSI > flags u` so programs can opt into the kitty key encoding
+            kitty_keyboard: true,
+            ..term::Config::default()
+        };
         let terminal_size = TerminalSize::default();
         let pty = tty::new(&pty_config, terminal_size.into(), id)?;
         let pty_id = pty.child().id();
+        let reported_cwd = crate::pty_tap::ReportedCwd::default();
+        #[cfg(unix)]
+        let pty = crate::pty_tap::TappedPty::new(pty, reported_cwd.clone())?;
         let (event_sender, event_receiver) = mpsc::channel();
         let event_proxy = EventProxy(event_sender);
-        let mut term = Term::new(config, &terminal_size, event_proxy.clone());
+        let mut term = Term::new(config.clone(), &terminal_size, event_proxy.clone());
         let initial_content = RenderableContent {
             grid: term.grid().clone(),
             selectable_range: None,
@@ -148,19 +177,28 @@ impl TerminalBackend {
             cursor: term.grid_mut().cursor_cell().clone(),
             cursor_line: 0,
             cursor_col: 0,
+            colors: *term.colors(),
+            cursor_style: term.cursor_style(),
         };
         let term = Arc::new(FairMutex::new(term));
         let pty_event_loop = EventLoop::new(term.clone(), event_proxy, pty, false, false)?;
         let notifier = Notifier(pty_event_loop.channel());
         let _pty_event_loop_thread = pty_event_loop.spawn();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let queue = events.clone();
         let _pty_event_subscription = std::thread::Builder::new()
             .name(format!("pty_event_subscription_{}", id))
-            .spawn(move || loop {
-                if let Ok(event) = event_receiver.recv() {
-                    println!(">>>>>>>>>>>>Received event: {:?}", event);
+            .spawn(move || {
+                while let Ok(event) = event_receiver.recv() {
+                    let exit = matches!(event, Event::Exit);
+                    // Wakeups only mean "redraw"; the signal alone covers them
+                    if !matches!(event, Event::Wakeup | Event::MouseCursorDirty) {
+                        ::log::debug!("PTY {} event: {:?}", id, event);
+                        queue.lock().unwrap().push(event);
+                    }
                     // 바로 UI에 신호 전송 - 불필요한 중간 채널 제거
                     signal.set();
-                    if let Event::Exit = event {
+                    if exit {
                         break;
                     }
                 }
@@ -173,6 +211,13 @@ impl TerminalBackend {
             size: terminal_size,
             notifier,
             last_content: initial_content,
+            config,
+            settings,
+            events,
+            title: None,
+            exit_code: None,
+            exited: false,
+            reported_cwd,
         })
     }
 
@@ -190,6 +235,21 @@ impl TerminalBackend {
             BackendCommand::Resize(layout_size, font_size) => {
                 self.resize(&mut term, layout_size, font_size);
             },
+            BackendCommand::SetScrollback(lines) => {
+                self.config.scrolling_history = lines;
+                term.set_options(self.config.clone());
+            },
+            BackendCommand::SelectStart(selection_type, point, side) => {
+                term.selection = Some(Selection::new(selection_type, point, side));
+            },
+            BackendCommand::SelectUpdate(point, side) => {
+                if let Some(selection) = term.selection.as_mut() {
+                    selection.update(point, side);
+                }
+            },
+            BackendCommand::ClearSelection => {
+                term.selection = None;
+            },
         };
     }
 
@@ -210,10 +270,25 @@ impl TerminalBackend {
         self.last_content.terminal_mode = *terminal.mode();
         self.last_content.terminal_size = self.size;
         self.last_content.cursor_line = point.line.0 as usize;
-        self.last_content.cursor_col = point.column.0 as usize;
+        self.last_content.cursor_col = point.column.0;
+        self.last_content.colors = *terminal.colors();
+        self.last_content.cursor_style = terminal.cursor_style();
         &self.last_content
     }
 
+    /// Selected text; alacritty joins wrapped lines and emits wide chars once
+    pub fn selection_text(&self) -> Option<String> {
+        self.term
+            .lock()
+            .selection_to_string()
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Current terminal modes, read live (last_content may lag behind PTY output)
+    pub fn mode(&self) -> TermMode {
+        *self.term.lock().mode()
+    }
+
     pub fn last_content(&self) -> &RenderableContent {
         &self.last_content
     }
@@ -226,6 +301,60 @@ impl TerminalBackend {
         self.pty_id
     }
 
+    /// Current directory: what the shell last reported through OSC 7, else the
+    /// foreground process's cwd from /proc (Linux)
+    pub fn working_directory(&self) -> Option<PathBuf> {
+        self.reported_cwd
+            .lock()
+            .unwrap()
+            .clone()
+            .or_else(|| crate::process::foreground_cwd(self.pty_id))
+    }
+
+    /// Take the alacritty events queued since the last call
+    pub fn take_events(&self) -> Vec<Event> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+
+    /// Window title the program set with OSC 0/2, if any
+    pub fn title(&self) -> Option<String> {
+        self.title.clone().filter(|title| !title.is_empty())
+    }
+
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.title = title;
+    }
+
+    /// Send a reply (PtyWrite, color or size report) without scrolling the view
+    pub fn reply(&self, text: String) {
+        self.write(text.into_bytes());
+    }
+
+    /// Size in cells and pixels, as reported for CSI 14/18 t
+    pub fn window_size(&self) -> WindowSize {
+        self.size.into()
+    }
+
+    pub fn settings(&self) -> &BackendSettings {
+        &self.settings
+    }
+
+    /// The shell has exited (its exit code, if it was reported)
+    pub fn mark_exited(&mut self, exit_code: Option<i32>) {
+        self.exited = true;
+        if exit_code.is_some() {
+            self.exit_code = exit_code;
+        }
+    }
+
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
     fn resize(&mut self, terminal: &mut Term<EventProxy>, layout_size: Size, font_size: Size) {
         if layout_size == self.size.layout_size
             && font_size.width as u16 == self.size.cell_width
@@ -297,6 +426,10 @@ pub struct RenderableContent {
     pub terminal_size: TerminalSize,
     pub cursor_line: usize,
     pub cursor_col: usize,
+    /// Palette overrides set by the application (OSC 4/10/11)
+    pub colors: Colors,
+    /// Shape and blinking requested through DECSCUSR
+    pub cursor_style: CursorStyle,
 }
 
 impl Default for RenderableContent {
@@ -309,6 +442,8 @@ impl Default for RenderableContent {
             terminal_size: TerminalSize::default(),
             cursor_line: 0,
             cursor_col: 0,
+            colors: Colors::default(),
+            cursor_style: CursorStyle::default(),
         }
     }
 }
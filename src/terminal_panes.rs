@@ -0,0 +1,172 @@
+// ModernTerm - Terminal Panes Widget
+// Lays out the visible tabs' split trees (or zoomed panes) with one TerminalView per pane
+
+use crate::layout;
+use crate::terminal_view::TerminalView;
+use crate::types::{AppState, SplitDirection, ViewMode};
+use makepad_widgets::*;
+
+live_design!{
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use crate::terminal_view::TerminalView;
+
+    pub TerminalPanesBase = {{TerminalPanes}} {}
+    pub TerminalPanes = <TerminalPanesBase> {
+        width: Fill,
+        height: Fill,
+
+        // Shows through the gaps between panes
+        draw_divider: { color: #3c3c3c }
+        pane: <TerminalView> {}
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct TerminalPanes {
+    #[walk] walk: Walk,
+
+    #[live] draw_divider: DrawColor,
+    #[live] pane: Option<LivePtr>,
+
+    #[redraw] #[rust] area: Area,
+    // One view per terminal id, kept across frames so selection/blink state survives
+    #[rust] panes: ComponentMap<u64, TerminalView>,
+    // Divider or grid gutter being dragged with the mouse
+    #[rust] drag: Option<DragTarget>,
+}
+
+impl Widget for TerminalPanes {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        self.draw_divider.draw_abs(cx, rect);
+
+        let rects = scope
+            .data
+            .get::<AppState>()
+            .map(|state| state.visible_pane_rects(rect))
+            .unwrap_or_default();
+
+        for (terminal_id, pane_rect) in rects {
+            let template = self.pane;
+            let pane = self.panes.get_or_insert(cx, terminal_id, |cx| TerminalView::new_from_ptr(cx, template));
+            pane.set_terminal_id(terminal_id);
+            let _ = pane.draw_walk(cx, scope, Walk::abs_rect(pane_rect));
+        }
+        // Drop views of panes that were closed or belong to other tabs
+        self.panes.retain_visible();
+
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        // Dividers first so their grab margin wins over the panes next to them
+        self.handle_dividers(cx, event, scope);
+        for pane in self.panes.values_mut() {
+            pane.handle_event(cx, event, scope);
+        }
+    }
+}
+
+/// Something in the pane area that can be dragged to resize panes
+#[derive(Debug, Clone)]
+enum DragTarget {
+    /// A split divider inside a tab
+    Divider(u64, layout::Divider),
+    /// A gutter between grid columns or rows
+    Gutter(layout::GridGutter),
+}
+
+impl DragTarget {
+    fn grabs(&self, abs: DVec2) -> bool {
+        match self {
+            DragTarget::Divider(_, divider) => divider.grabs(abs),
+            DragTarget::Gutter(gutter) => gutter.grabs(abs),
+        }
+    }
+
+    fn cursor(&self) -> MouseCursor {
+        let columns = match self {
+            DragTarget::Divider(_, divider) => divider.direction == SplitDirection::Vertical,
+            DragTarget::Gutter(gutter) => gutter.axis == layout::GridAxis::Columns,
+        };
+        if columns { MouseCursor::ColResize } else { MouseCursor::RowResize }
+    }
+}
+
+impl TerminalPanes {
+    /// Drag split dividers and grid gutters; ratios update live, PTY resizes are debounced
+    fn handle_dividers(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let Some(state) = scope.data.get_mut::<AppState>() else {
+            return;
+        };
+        let targets = Self::drag_targets(state, self.area.rect(cx));
+        let target_at = |abs: DVec2| targets.iter().find(|target| target.grabs(abs));
+
+        match event.hits_with_test(cx, self.area, |abs, _, _| target_at(abs).is_some()) {
+            Hit::FingerHoverIn(fh) | Hit::FingerHoverOver(fh) => {
+                if let Some(target) = target_at(fh.abs) {
+                    cx.set_cursor(target.cursor());
+                }
+            }
+            Hit::FingerDown(fe) if fe.is_primary_hit() => {
+                self.drag = target_at(fe.abs).cloned();
+                if let Some(target) = &self.drag {
+                    cx.set_cursor(target.cursor());
+                }
+            }
+            Hit::FingerMove(fe) => {
+                let changed = match &self.drag {
+                    Some(DragTarget::Divider(tab_id, divider)) => {
+                        let ratio = divider.ratio_at(fe.abs);
+                        state
+                            .tab_layouts
+                            .get_mut(tab_id)
+                            .is_some_and(|layout| layout.set_ratio(&divider.path, ratio))
+                    }
+                    Some(DragTarget::Gutter(gutter)) => {
+                        let fraction = gutter.fraction_at(fe.abs);
+                        if let ViewMode::Grid { rows, cols, col_ratios, row_ratios } = &mut state.view_mode {
+                            match gutter.axis {
+                                layout::GridAxis::Columns => layout::set_track_boundary(col_ratios, *cols, gutter.index, fraction),
+                                layout::GridAxis::Rows => layout::set_track_boundary(row_ratios, *rows, gutter.index, fraction),
+                            }
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    None => false,
+                };
+                if changed {
+                    state.schedule_pane_resize(cx);
+                    self.redraw(cx);
+                }
+            }
+            Hit::FingerUp(_) if self.drag.is_some() => {
+                self.drag = None;
+                state.schedule_pane_resize(cx);
+            }
+            _ => {}
+        }
+    }
+
+    /// Split dividers of every visible tab (none for zoomed panes) plus grid gutters
+    fn drag_targets(state: &AppState, rect: Rect) -> Vec<DragTarget> {
+        let mut targets: Vec<DragTarget> = state
+            .visible_tab_areas(rect)
+            .into_iter()
+            .filter_map(|(tab_id, area)| {
+                let dividers = layout::dividers(&*state.visible_layout(tab_id)?, area);
+                Some(dividers.into_iter().map(move |divider| DragTarget::Divider(tab_id, divider)))
+            })
+            .flatten()
+            .collect();
+        if let ViewMode::Grid { rows, cols, col_ratios, row_ratios } = &state.view_mode {
+            let gutters = layout::grid_gutters(state.tab_order.len(), *rows, *cols, col_ratios, row_ratios, rect);
+            targets.extend(gutters.into_iter().map(DragTarget::Gutter));
+        }
+        targets
+    }
+}
@@ -0,0 +1,553 @@
+// ModernTerm - Terminal View Widget
+// Draws an alacritty grid cell-by-cell (colors, attributes, wide chars, selection)
+
+use crate::mouse::{self, MouseAction, MouseReport};
+use crate::terminal::{BackendCommand, RenderableContent};
+use crate::theme::{to_vec4, Theme};
+use crate::types::{AppState, Size, ViewMode};
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Point, Side};
+use alacritty_terminal::selection::SelectionType;
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::{point_to_viewport, viewport_to_point, TermMode};
+use alacritty_terminal::vte::ansi::{Color, CursorShape, NamedColor};
+use makepad_widgets::*;
+
+/// Thickness of the focus and broadcast frames
+const FRAME_WIDTH: f64 = 2.0;
+
+live_design!{
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    pub D2_CODING_FONT = {
+        font_family:{
+            latin = font("crate://self/assets/fonts/D2Coding.ttf", 0.0, 0.0),
+            cjk = font("crate://self/assets/fonts/D2Coding.ttf", 0.0, -0.5),
+        },
+        line_spacing: 1.0
+    }
+
+    pub TerminalViewBase = {{TerminalView}} {}
+    pub TerminalView = <TerminalViewBase> {
+        width: Fill,
+        height: Fill,
+        padding: {left: 12, right: 12, top: 12, bottom: 12},
+
+        draw_bg: { color: #1e1e1e }
+        draw_cell_bg: {}
+        draw_text: {
+            text_style: <D2_CODING_FONT> { font_size: 9.0 }
+        }
+        // Same font, slanted in the vertex shader (D2Coding ships no italic face)
+        draw_text_italic: {
+            text_style: <D2_CODING_FONT> { font_size: 9.0 }
+            fn vertex(self) -> vec4 {
+                let p = mix(self.rect_pos, self.rect_pos + self.rect_size, self.geom_pos);
+                let p_clipped = clamp(p, self.draw_clip.xy, self.draw_clip.zw);
+                let p_normalized: vec2 = (p_clipped - self.rect_pos) / self.rect_size;
+
+                self.pos = p_normalized;
+                self.t = mix(self.t_min, self.t_max, p_normalized.xy);
+                let skew = (1.0 - p_normalized.y) * self.rect_size.y * 0.2;
+                self.world = self.view_transform * vec4(
+                    p_clipped.x + skew,
+                    p_clipped.y,
+                    self.glyph_depth + self.draw_zbias,
+                    1.
+                );
+                return self.camera_projection * (self.camera_view * (self.world));
+            }
+        }
+        draw_decoration: {}
+        // Frame around the focused pane when a tab is split
+        draw_focus: { color: #4a90d9 }
+        // Frame around panes that receive broadcast input
+        draw_broadcast: { color: #e05252 }
+        // Bar across the bottom of a pane whose shell has exited
+        draw_exit_banner: { color: #303030 }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct TerminalView {
+    #[walk] walk: Walk,
+    #[live] padding: Padding,
+
+    #[live] draw_bg: DrawColor,
+    #[live] draw_cell_bg: DrawColor,
+    #[live] draw_text: DrawText,
+    #[live] draw_text_italic: DrawText,
+    #[live] draw_decoration: DrawColor,
+    #[live] draw_focus: DrawColor,
+    #[live] draw_broadcast: DrawColor,
+    #[live] draw_exit_banner: DrawColor,
+
+    #[redraw] #[rust] area: Area,
+    #[rust] terminal_id: Option<u64>,
+
+    // Cursor blinking (DECSCUSR odd styles), driven by an interval timer
+    #[rust] blink_timer: Timer,
+    #[rust] blink_interval_ms: u64,
+    #[rust] blink_off: bool,
+    #[rust] blink_anchor: Option<Point>,
+
+    // A primary-button drag is extending the selection
+    #[rust] selecting: bool,
+    // Button held while the program tracks the mouse, and the last reported cell
+    #[rust] report_button: Option<mouse::MouseButton>,
+    #[rust] last_report_cell: Option<Point<usize>>,
+}
+
+impl Widget for TerminalView {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle_with_area(&mut self.area, walk);
+        let Some(state) = scope.data.get::<AppState>() else {
+            self.draw_bg.draw_abs(cx, rect);
+            return DrawStep::done();
+        };
+        let backend = self.terminal_id.and_then(|id| state.terminals.get(&id));
+        // OSC 11 may override the theme background per terminal
+        self.draw_bg.color = match backend {
+            Some(backend) => to_vec4(state.theme.lookup(NamedColor::Background as usize, &backend.last_content().colors)),
+            None => state.theme.background_vec4(),
+        };
+        self.draw_bg.draw_abs(cx, rect);
+        let Some(backend) = backend else {
+            return DrawStep::done();
+        };
+
+        let font_size = state.config.appearance.font_size;
+        self.draw_text.text_style.font_size = font_size;
+        self.draw_text_italic.text_style.font_size = font_size;
+
+        let content = backend.last_content();
+        let focused = state.window_focused && state.focused_terminal == self.terminal_id;
+        let blinking = focused && content.cursor_style.blinking;
+        self.update_blink(cx, blinking, state.config.appearance.cursor_blink_interval);
+        // Show the cursor again whenever it moves so typing never lands in an "off" phase
+        if self.blink_anchor != Some(content.grid.cursor.point) {
+            self.blink_anchor = Some(content.grid.cursor.point);
+            self.blink_off = false;
+        }
+        let cursor_shape = self.cursor_shape(content, focused);
+
+        let origin = dvec2(rect.pos.x + self.padding.left, rect.pos.y + self.padding.top);
+        self.draw_content(cx, origin, state.config.appearance.cell_size(), &state.theme, cursor_shape, content);
+        if backend.has_exited() {
+            self.draw_exit_banner(cx, rect, state, backend.exit_code());
+        }
+
+        // Broadcast targets get a red frame; the focus frame sits just inside it
+        let mut frame = rect;
+        if self.terminal_id.is_some_and(|id| state.is_broadcast_target(id)) {
+            Self::draw_frame(cx, &mut self.draw_broadcast, frame);
+            frame = Rect { pos: frame.pos + dvec2(FRAME_WIDTH, FRAME_WIDTH), size: frame.size - dvec2(2.0 * FRAME_WIDTH, 2.0 * FRAME_WIDTH) };
+        }
+        if state.focused_terminal == self.terminal_id && state.shows_multiple_panes() {
+            Self::draw_frame(cx, &mut self.draw_focus, frame);
+        }
+
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if self.blink_timer.is_event(event).is_some() {
+            self.blink_off = !self.blink_off;
+            self.redraw(cx);
+        }
+
+        let Some(terminal_id) = self.terminal_id else {
+            return;
+        };
+        let Some(state) = scope.data.get_mut::<AppState>() else {
+            return;
+        };
+
+        // Programs with mouse tracking get the events; Shift keeps local selection/scrolling
+        let reporting = state
+            .terminals
+            .get(&terminal_id)
+            .is_some_and(|backend| mouse::is_reporting(backend.last_content().terminal_mode));
+
+        let hit = event.hits(cx, self.area);
+        if let Hit::FingerDown(fe) = &hit {
+            // Ctrl+click picks broadcast targets without moving focus or selecting
+            if state.broadcast_mode && fe.modifiers.control {
+                state.toggle_broadcast_selection(terminal_id);
+                cx.redraw_all();
+                return;
+            }
+            // Any click focuses the pane (other panes redraw their cursor hollow)
+            if state.focused_terminal != Some(terminal_id) {
+                state.focus_terminal(terminal_id);
+                cx.redraw_all();
+            }
+            // Double-click in grid view opens that tab in Single mode
+            if fe.tap_count == 2 && matches!(state.view_mode, ViewMode::Grid { .. }) {
+                state.set_single_view();
+                state.schedule_pane_resize(cx);
+                cx.redraw_all();
+                return;
+            }
+        }
+
+        match hit {
+            Hit::FingerHoverIn(_) => {
+                cx.set_cursor(if reporting { MouseCursor::Default } else { MouseCursor::Text });
+            }
+            Hit::FingerHoverOver(fh) if reporting && !fh.modifiers.shift => {
+                self.report_mouse(cx, state, terminal_id, mouse::MouseButton::None, MouseAction::Motion, fh.abs, fh.modifiers);
+            }
+            Hit::FingerScroll(fs) => {
+                if reporting && !fs.modifiers.shift {
+                    let button = if fs.scroll.y < 0.0 { mouse::MouseButton::WheelUp } else { mouse::MouseButton::WheelDown };
+                    self.report_mouse(cx, state, terminal_id, button, MouseAction::Press, fs.abs, fs.modifiers);
+                } else {
+                    self.scroll_history(cx, state, terminal_id, fs.scroll.y);
+                }
+            }
+            Hit::FingerDown(fe) if reporting && !fe.modifiers.shift => {
+                let button = match fe.mouse_button() {
+                    Some(button) if button.is_middle() => mouse::MouseButton::Middle,
+                    Some(button) if button.is_secondary() => mouse::MouseButton::Right,
+                    _ => mouse::MouseButton::Left,
+                };
+                self.report_button = Some(button);
+                self.report_mouse(cx, state, terminal_id, button, MouseAction::Press, fe.abs, fe.modifiers);
+            }
+            Hit::FingerMove(fe) if self.report_button.is_some() => {
+                if let Some(button) = self.report_button {
+                    self.report_mouse(cx, state, terminal_id, button, MouseAction::Motion, fe.abs, fe.modifiers);
+                }
+            }
+            Hit::FingerUp(fe) if self.report_button.is_some() => {
+                if let Some(button) = self.report_button.take() {
+                    self.report_mouse(cx, state, terminal_id, button, MouseAction::Release, fe.abs, fe.modifiers);
+                }
+            }
+            Hit::FingerDown(fe) if fe.is_primary_hit() => {
+                // Alt-drag selects a rectangle; 2/3 clicks select words/lines
+                let selection_type = if fe.modifiers.alt {
+                    SelectionType::Block
+                } else {
+                    match fe.tap_count {
+                        2 => SelectionType::Semantic,
+                        3.. => SelectionType::Lines,
+                        _ => SelectionType::Simple,
+                    }
+                };
+                if let Some((point, side)) = self.grid_point(cx, state, terminal_id, fe.abs) {
+                    self.selecting = true;
+                    self.update_selection(cx, state, terminal_id, BackendCommand::SelectStart(selection_type, point, side));
+                }
+            }
+            Hit::FingerMove(fe) if self.selecting => {
+                if let Some((point, side)) = self.grid_point(cx, state, terminal_id, fe.abs) {
+                    self.update_selection(cx, state, terminal_id, BackendCommand::SelectUpdate(point, side));
+                }
+            }
+            Hit::FingerUp(_) if self.selecting => {
+                self.selecting = false;
+                if state.config.behavior.copy_on_select {
+                    if let Some(text) = state.terminals.get(&terminal_id).and_then(|b| b.selection_text()) {
+                        cx.copy_to_clipboard(&text);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl TerminalView {
+    /// Choose which terminal backend this view renders (used by the pane host)
+    pub fn set_terminal_id(&mut self, terminal_id: u64) {
+        self.terminal_id = Some(terminal_id);
+    }
+
+    /// Map a window position to the viewport cell and cell half under it, clamped to the grid
+    fn viewport_cell(&self, cx: &Cx, state: &AppState, terminal_id: u64, abs: DVec2) -> Option<(Point<usize>, Side)> {
+        let grid = &state.terminals.get(&terminal_id)?.last_content().grid;
+        if grid.columns() == 0 || grid.screen_lines() == 0 {
+            return None;
+        }
+        let cell_size = state.config.appearance.cell_size();
+        let (cell_width, cell_height) = (cell_size.width as f64, cell_size.height as f64);
+        let rect = self.area.rect(cx);
+        let x = abs.x - rect.pos.x - self.padding.left;
+        let y = abs.y - rect.pos.y - self.padding.top;
+
+        let column = (x / cell_width).floor().clamp(0.0, (grid.columns() - 1) as f64) as usize;
+        let line = (y / cell_height).floor().clamp(0.0, (grid.screen_lines() - 1) as f64) as usize;
+        let side = if x - column as f64 * cell_width > cell_width / 2.0 {
+            Side::Right
+        } else {
+            Side::Left
+        };
+        Some((Point::new(line, Column(column)), side))
+    }
+
+    /// Grid point (scrollback aware) under a window position
+    fn grid_point(&self, cx: &Cx, state: &AppState, terminal_id: u64, abs: DVec2) -> Option<(Point, Side)> {
+        let (viewport_point, side) = self.viewport_cell(cx, state, terminal_id, abs)?;
+        let display_offset = state.terminals.get(&terminal_id)?.last_content().grid.display_offset();
+        Some((viewport_to_point(display_offset, viewport_point), side))
+    }
+
+    /// Send a mouse event to the program; motion is only reported when the cell changes
+    #[allow(clippy::too_many_arguments)]
+    fn report_mouse(
+        &mut self,
+        cx: &Cx,
+        state: &mut AppState,
+        terminal_id: u64,
+        button: mouse::MouseButton,
+        action: MouseAction,
+        abs: DVec2,
+        modifiers: KeyModifiers,
+    ) {
+        let Some((point, _)) = self.viewport_cell(cx, state, terminal_id, abs) else {
+            return;
+        };
+        if action == MouseAction::Motion && self.last_report_cell == Some(point) {
+            return;
+        }
+        self.last_report_cell = Some(point);
+
+        let Some(backend) = state.terminals.get_mut(&terminal_id) else {
+            return;
+        };
+        let report = MouseReport { button, action, point, modifiers };
+        if let Some(bytes) = mouse::encode(&report, backend.last_content().terminal_mode) {
+            backend.process_command(BackendCommand::Write(bytes));
+        }
+    }
+
+    /// Scroll this terminal's history (or send arrows on the alternate screen)
+    fn scroll_history(&mut self, cx: &mut Cx, state: &mut AppState, terminal_id: u64, scroll_y: f64) {
+        // Negative delta = scroll up (show older content)
+        let delta = -(scroll_y * 3.0) as i32;
+        let Some(backend) = state.terminals.get_mut(&terminal_id) else {
+            return;
+        };
+        ::log::info!("📜 Scroll event: delta={}, terminal_id={}", delta, terminal_id);
+        backend.process_command(BackendCommand::Scroll(delta));
+        backend.sync();
+        // User scrolled: disable follow at bottom until they return
+        state.follow_output.insert(terminal_id, false);
+        self.redraw(cx);
+    }
+
+    fn update_selection(&mut self, cx: &mut Cx, state: &mut AppState, terminal_id: u64, command: BackendCommand) {
+        if let Some(backend) = state.terminals.get_mut(&terminal_id) {
+            backend.process_command(command);
+            backend.sync();
+            self.redraw(cx);
+        }
+    }
+
+    /// Effective cursor shape: hidden by DECTCEM or the blink phase, hollow when unfocused
+    fn cursor_shape(&self, content: &RenderableContent, focused: bool) -> CursorShape {
+        if !content.terminal_mode.contains(TermMode::SHOW_CURSOR) || self.blink_off {
+            return CursorShape::Hidden;
+        }
+        match content.cursor_style.shape {
+            CursorShape::Hidden => CursorShape::Hidden,
+            _ if !focused => CursorShape::HollowBlock,
+            shape => shape,
+        }
+    }
+
+    /// Start, restart or stop the blink timer to match the wanted interval (0 = steady)
+    fn update_blink(&mut self, cx: &mut Cx, blinking: bool, interval_ms: u64) {
+        let interval_ms = if blinking { interval_ms } else { 0 };
+        if interval_ms == self.blink_interval_ms {
+            return;
+        }
+        cx.stop_timer(self.blink_timer);
+        self.blink_timer = Timer::default();
+        self.blink_interval_ms = interval_ms;
+        self.blink_off = false;
+        if interval_ms > 0 {
+            self.blink_timer = cx.start_interval(interval_ms as f64 / 1000.0);
+        }
+    }
+
+    fn draw_content(
+        &mut self,
+        cx: &mut Cx2d,
+        origin: DVec2,
+        cell_size: Size,
+        theme: &Theme,
+        cursor_shape: CursorShape,
+        content: &RenderableContent,
+    ) {
+        let grid = &content.grid;
+        let display_offset = grid.display_offset();
+        let cell_width = cell_size.width as f64;
+        let cell_height = cell_size.height as f64;
+        let cell_rect = |point: Point<usize>, columns: usize| Rect {
+            pos: dvec2(
+                origin.x + point.column.0 as f64 * cell_width,
+                origin.y + point.line as f64 * cell_height,
+            ),
+            size: dvec2(cell_width * columns as f64, cell_height),
+        };
+
+        // Cursor, only while its line is inside the viewport
+        let cursor_point = grid.cursor.point;
+        let cursor_visible = cursor_shape != CursorShape::Hidden
+            && point_to_viewport(display_offset, cursor_point).is_some();
+        let cursor_color = to_vec4(theme.lookup(NamedColor::Cursor as usize, &content.colors));
+        let mut cursor_rect = None;
+
+        // Resolve every visible cell once; backgrounds, glyphs and decorations are
+        // drawn in separate passes so each primitive type stays in a single draw call.
+        let mut cells = Vec::with_capacity(grid.screen_lines() * grid.columns());
+        for indexed in grid.display_iter() {
+            if indexed.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                continue;
+            }
+            let Some(viewport_point) = point_to_viewport(display_offset, indexed.point) else {
+                continue;
+            };
+
+            let mut fg = theme.resolve(indexed.fg, indexed.flags, &content.colors);
+            let mut bg = theme.resolve(indexed.bg, Flags::empty(), &content.colors);
+            if indexed.flags.contains(Flags::INVERSE) {
+                std::mem::swap(&mut fg, &mut bg);
+            }
+            let selected = content
+                .selectable_range
+                .is_some_and(|range| range.contains(indexed.point));
+            if selected {
+                bg = to_vec4(theme.selection_background);
+                if let Some(selection_fg) = theme.selection_foreground {
+                    fg = to_vec4(selection_fg);
+                }
+            }
+            let columns = if indexed.flags.contains(Flags::WIDE_CHAR) { 2 } else { 1 };
+            let rect = cell_rect(viewport_point, columns);
+            if cursor_visible && indexed.point == cursor_point {
+                cursor_rect = Some(rect);
+                // A block cursor inverts the cell; other shapes are drawn on top afterwards
+                if cursor_shape == CursorShape::Block {
+                    bg = cursor_color;
+                    fg = to_vec4(theme.cursor_text);
+                }
+            }
+
+            let mut text = String::new();
+            if indexed.c != ' ' && indexed.c != '\t' && !indexed.flags.contains(Flags::HIDDEN) {
+                text.push(indexed.c);
+                if let Some(zerowidth) = indexed.zerowidth() {
+                    text.extend(zerowidth);
+                }
+            }
+
+            cells.push((rect, indexed.flags, fg, bg, text));
+        }
+
+        let default_bg = theme.resolve(Color::Named(NamedColor::Background), Flags::empty(), &content.colors);
+        for (rect, _, _, bg, _) in &cells {
+            if *bg != default_bg {
+                self.draw_cell_bg.color = *bg;
+                self.draw_cell_bg.draw_abs(cx, *rect);
+            }
+        }
+
+        for (rect, flags, fg, _, text) in &cells {
+            if text.is_empty() {
+                continue;
+            }
+            let draw_text = if flags.contains(Flags::ITALIC) {
+                &mut self.draw_text_italic
+            } else {
+                &mut self.draw_text
+            };
+            draw_text.color = *fg;
+            draw_text.draw_abs(cx, rect.pos, text);
+            if flags.contains(Flags::BOLD) {
+                // Faux bold: overdraw shifted by half a pixel
+                draw_text.draw_abs(cx, rect.pos + dvec2(0.5, 0.0), text);
+            }
+        }
+
+        for (rect, flags, fg, _, _) in &cells {
+            self.draw_decoration.color = *fg;
+            if flags.intersects(Flags::ALL_UNDERLINES) {
+                let y = rect.pos.y + rect.size.y - 1.0;
+                self.draw_decoration.draw_abs(cx, Rect { pos: dvec2(rect.pos.x, y), size: dvec2(rect.size.x, 1.0) });
+                if flags.contains(Flags::DOUBLE_UNDERLINE) {
+                    self.draw_decoration.draw_abs(cx, Rect { pos: dvec2(rect.pos.x, y - 2.0), size: dvec2(rect.size.x, 1.0) });
+                }
+            }
+            if flags.contains(Flags::STRIKEOUT) {
+                let y = rect.pos.y + (rect.size.y / 2.0).floor();
+                self.draw_decoration.draw_abs(cx, Rect { pos: dvec2(rect.pos.x, y), size: dvec2(rect.size.x, 1.0) });
+            }
+        }
+
+        if let Some(rect) = cursor_rect {
+            self.draw_cursor_overlay(cx, rect, cursor_shape, cursor_color);
+        }
+    }
+
+    /// "[Process exited ...]" bar over the last line of the pane
+    fn draw_exit_banner(&mut self, cx: &mut Cx2d, rect: Rect, state: &AppState, exit_code: Option<i32>) {
+        let cell_size = state.config.appearance.cell_size();
+        let height = cell_size.height as f64 + self.padding.bottom;
+        let banner = Rect {
+            pos: dvec2(rect.pos.x, rect.pos.y + rect.size.y - height),
+            size: dvec2(rect.size.x, height),
+        };
+        self.draw_exit_banner.draw_abs(cx, banner);
+
+        let message = match exit_code {
+            Some(code) => format!("[Process exited with code {} — press Enter to restart]", code),
+            None => "[Process exited — press Enter to restart]".to_string(),
+        };
+        self.draw_text.color = state.theme.foreground_vec4();
+        let text_pos = dvec2(rect.pos.x + self.padding.left, banner.pos.y + (self.padding.bottom / 2.0).floor());
+        self.draw_text.draw_abs(cx, text_pos, &message);
+    }
+
+    fn draw_frame(cx: &mut Cx2d, draw: &mut DrawColor, rect: Rect) {
+        let width = FRAME_WIDTH;
+        let edges = [
+            Rect { pos: rect.pos, size: dvec2(rect.size.x, width) },
+            Rect { pos: dvec2(rect.pos.x, rect.pos.y + rect.size.y - width), size: dvec2(rect.size.x, width) },
+            Rect { pos: rect.pos, size: dvec2(width, rect.size.y) },
+            Rect { pos: dvec2(rect.pos.x + rect.size.x - width, rect.pos.y), size: dvec2(width, rect.size.y) },
+        ];
+        for edge in edges {
+            draw.draw_abs(cx, edge);
+        }
+    }
+
+    /// Beam, underline and hollow cursors, drawn over the glyphs
+    fn draw_cursor_overlay(&mut self, cx: &mut Cx2d, rect: Rect, shape: CursorShape, color: Vec4) {
+        // Same proportion alacritty uses for beam/underline thickness
+        let thickness = (rect.size.x * 0.15).round().max(1.0);
+        let bars: Vec<Rect> = match shape {
+            CursorShape::Beam => vec![Rect { pos: rect.pos, size: dvec2(thickness, rect.size.y) }],
+            CursorShape::Underline => vec![Rect {
+                pos: dvec2(rect.pos.x, rect.pos.y + rect.size.y - thickness),
+                size: dvec2(rect.size.x, thickness),
+            }],
+            CursorShape::HollowBlock => vec![
+                Rect { pos: rect.pos, size: dvec2(rect.size.x, 1.0) },
+                Rect { pos: dvec2(rect.pos.x, rect.pos.y + rect.size.y - 1.0), size: dvec2(rect.size.x, 1.0) },
+                Rect { pos: rect.pos, size: dvec2(1.0, rect.size.y) },
+                Rect { pos: dvec2(rect.pos.x + rect.size.x - 1.0, rect.pos.y), size: dvec2(1.0, rect.size.y) },
+            ],
+            CursorShape::Block | CursorShape::Hidden => Vec::new(),
+        };
+        self.draw_decoration.color = color;
+        for bar in bars {
+            self.draw_decoration.draw_abs(cx, bar);
+        }
+    }
+}
@@ -0,0 +1,435 @@
+// ModernTerm - Color Themes
+// Built-in and user themes plus resolution of alacritty colors into RGBA
+
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::color::{Colors, COUNT};
+use alacritty_terminal::vte::ansi::{Color, NamedColor, Rgb};
+use makepad_widgets::{vec4, Vec4};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Names accepted for `appearance.theme` without a theme file
+pub const BUILTIN_THEMES: &[&str] = &["dark", "light", "solarized", "dracula"];
+
+/// A fully resolved color theme
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub foreground: Rgb,
+    pub background: Rgb,
+    pub cursor: Rgb,
+    /// Color of the character under a block cursor
+    pub cursor_text: Rgb,
+    pub selection_background: Rgb,
+    /// Keep the cell's own foreground when None
+    pub selection_foreground: Option<Rgb>,
+    /// Full xterm palette laid out like alacritty's `Colors` (see term::color)
+    palette: [Rgb; COUNT],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Look up a built-in theme by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" | "solarized-dark" | "solarized_dark" => Some(Self::solarized()),
+            "dracula" => Some(Self::dracula()),
+            _ => None,
+        }
+    }
+
+    /// Resolve `appearance.theme`: a built-in name, a name in the themes dir, or a path
+    pub fn load(name: &str) -> Result<Self, String> {
+        if let Some(theme) = Self::builtin(name) {
+            return Ok(theme);
+        }
+
+        let path = Self::theme_path(name)
+            .ok_or_else(|| format!("unknown theme `{}` (no config directory to search)", name))?;
+        let source = std::fs::read_to_string(&path).map_err(|e| {
+            format!(
+                "unknown theme `{}`: can't read {} ({}); built-in themes: {}",
+                name,
+                path.display(),
+                e,
+                BUILTIN_THEMES.join(", ")
+            )
+        })?;
+        let fallback_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| name.to_string());
+        Self::parse(&source, fallback_name).map_err(|e| format!("theme {}: {}", path.display(), e))
+    }
+
+    /// Build a theme from the source of a theme file (see `ThemeFile`)
+    fn parse(source: &str, fallback_name: String) -> Result<Self, String> {
+        let file: ThemeFile = toml::from_str(source).map_err(|e| e.message().trim().to_string())?;
+        file.into_theme(fallback_name)
+    }
+
+    fn theme_path(name: &str) -> Option<PathBuf> {
+        let path = PathBuf::from(name);
+        if path.is_absolute() {
+            return Some(path);
+        }
+        let themes_dir = crate::config::config_dir()?.join("themes");
+        if name.ends_with(".toml") {
+            Some(themes_dir.join(path))
+        } else {
+            Some(themes_dir.join(format!("{}.toml", name)))
+        }
+    }
+
+    /// Resolve a cell color, applying bold-as-bright and dim and honoring
+    /// palette overrides the application set through OSC 4/10/11.
+    pub fn resolve(&self, color: Color, flags: Flags, overrides: &Colors) -> Vec4 {
+        let dim = flags.contains(Flags::DIM);
+        let bold = flags.contains(Flags::BOLD);
+
+        let rgb = match color {
+            Color::Spec(rgb) if dim => scale(rgb, DIM_FACTOR),
+            Color::Spec(rgb) => rgb,
+            Color::Named(mut named) => {
+                if dim {
+                    named = named.to_dim();
+                } else if bold {
+                    named = named.to_bright();
+                }
+                self.lookup(named as usize, overrides)
+            }
+            Color::Indexed(index) => {
+                let index = index as usize;
+                match index {
+                    0..=7 if dim => self.lookup(NamedColor::DimBlack as usize + index, overrides),
+                    0..=7 if bold => self.lookup(index + 8, overrides),
+                    _ => self.lookup(index, overrides),
+                }
+            }
+        };
+        to_vec4(rgb)
+    }
+
+    /// Palette entry by alacritty color index (0..COUNT)
+    pub fn lookup(&self, index: usize, overrides: &Colors) -> Rgb {
+        overrides[index].unwrap_or(self.palette[index.min(COUNT - 1)])
+    }
+
+    pub fn foreground_vec4(&self) -> Vec4 {
+        to_vec4(self.foreground)
+    }
+
+    pub fn background_vec4(&self) -> Vec4 {
+        to_vec4(self.background)
+    }
+
+    fn new(name: &str, foreground: u32, background: u32, cursor: u32, selection: u32, ansi: [u32; 16]) -> Self {
+        let ansi = ansi.map(rgb);
+        let mut theme = Theme {
+            name: name.to_string(),
+            foreground: rgb(foreground),
+            background: rgb(background),
+            cursor: rgb(cursor),
+            cursor_text: rgb(background),
+            selection_background: rgb(selection),
+            selection_foreground: None,
+            palette: [Rgb::default(); COUNT],
+        };
+        theme.rebuild_palette(&ansi, None, None);
+        theme
+    }
+
+    /// Fill the 269-entry palette from the 16 ANSI colors and fg/bg/cursor
+    fn rebuild_palette(&mut self, ansi: &[Rgb; 16], dim: Option<&[Rgb; 8]>, bright_foreground: Option<Rgb>) {
+        self.palette[..16].copy_from_slice(ansi);
+
+        // 6x6x6 color cube
+        for r in 0..6 {
+            for g in 0..6 {
+                for b in 0..6 {
+                    let level = |v: usize| if v == 0 { 0 } else { (55 + v * 40) as u8 };
+                    self.palette[16 + r * 36 + g * 6 + b] = Rgb { r: level(r), g: level(g), b: level(b) };
+                }
+            }
+        }
+
+        // 24-step grayscale ramp
+        for i in 0..24 {
+            let value = (8 + i * 10) as u8;
+            self.palette[232 + i] = Rgb { r: value, g: value, b: value };
+        }
+
+        self.palette[NamedColor::Foreground as usize] = self.foreground;
+        self.palette[NamedColor::Background as usize] = self.background;
+        self.palette[NamedColor::Cursor as usize] = self.cursor;
+        for i in 0..8 {
+            self.palette[NamedColor::DimBlack as usize + i] = match dim {
+                Some(dim) => dim[i],
+                None => scale(ansi[i], DIM_FACTOR),
+            };
+        }
+        self.palette[NamedColor::BrightForeground as usize] = bright_foreground.unwrap_or(self.foreground);
+        self.palette[NamedColor::DimForeground as usize] = scale(self.foreground, DIM_FACTOR);
+    }
+
+    fn dark() -> Self {
+        Self::new("dark", 0xc0c0c0, 0x1e1e1e, 0xc0c0c0, 0x264f78, [
+            0x000000, 0xcd3131, 0x0dbc79, 0xe5e510, 0x2472c8, 0xbc3fbc, 0x11a8cd, 0xe5e5e5,
+            0x666666, 0xf14c4c, 0x23d18b, 0xf5f543, 0x3b8eea, 0xd670d6, 0x29b8db, 0xffffff,
+        ])
+    }
+
+    fn light() -> Self {
+        Self::new("light", 0x383a42, 0xfafafa, 0x526eff, 0xbfceff, [
+            0x383a42, 0xe45649, 0x50a14f, 0xc18401, 0x0184bc, 0xa626a4, 0x0997b3, 0xa0a1a7,
+            0x4f525e, 0xe06c75, 0x98c379, 0xe5c07b, 0x61afef, 0xc678dd, 0x56b6c2, 0xffffff,
+        ])
+    }
+
+    fn solarized() -> Self {
+        Self::new("solarized", 0x839496, 0x002b36, 0x93a1a1, 0x073642, [
+            0x073642, 0xdc322f, 0x859900, 0xb58900, 0x268bd2, 0xd33682, 0x2aa198, 0xeee8d5,
+            0x002b36, 0xcb4b16, 0x586e75, 0x657b83, 0x839496, 0x6c71c4, 0x93a1a1, 0xfdf6e3,
+        ])
+    }
+
+    fn dracula() -> Self {
+        Self::new("dracula", 0xf8f8f2, 0x282a36, 0xf8f8f2, 0x44475a, [
+            0x21222c, 0xff5555, 0x50fa7b, 0xf1fa8c, 0xbd93f9, 0xff79c6, 0x8be9fd, 0xf8f8f2,
+            0x6272a4, 0xff6e6e, 0x69ff94, 0xffffa5, 0xd6acff, 0xff92df, 0xa4ffff, 0xffffff,
+        ])
+    }
+}
+
+/// Brightness factor alacritty uses for dim colors
+const DIM_FACTOR: f32 = 0.66;
+
+fn rgb(value: u32) -> Rgb {
+    Rgb {
+        r: (value >> 16) as u8,
+        g: (value >> 8) as u8,
+        b: value as u8,
+    }
+}
+
+fn scale(color: Rgb, factor: f32) -> Rgb {
+    Rgb {
+        r: (color.r as f32 * factor) as u8,
+        g: (color.g as f32 * factor) as u8,
+        b: (color.b as f32 * factor) as u8,
+    }
+}
+
+pub fn to_vec4(color: Rgb) -> Vec4 {
+    vec4(color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0, 1.0)
+}
+
+fn parse_hex(value: &str) -> Result<Rgb, String> {
+    let hex = value
+        .trim()
+        .trim_start_matches('#')
+        .trim_start_matches("0x");
+    if hex.len() != 6 {
+        return Err(format!("invalid color `{}` (expected #rrggbb)", value));
+    }
+    u32::from_str_radix(hex, 16)
+        .map(rgb)
+        .map_err(|_| format!("invalid color `{}` (expected #rrggbb)", value))
+}
+
+/// On-disk theme format, alacritty-style:
+///
+/// ```toml
+/// base = "dark"          # optional built-in to inherit from
+/// foreground = "#c0c0c0"
+/// background = "#1e1e1e"
+/// [normal]
+/// red = "#cd3131"
+/// [bright]
+/// red = "#f14c4c"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    name: Option<String>,
+    base: Option<String>,
+    foreground: Option<String>,
+    background: Option<String>,
+    cursor: Option<String>,
+    cursor_text: Option<String>,
+    selection_background: Option<String>,
+    selection_foreground: Option<String>,
+    bright_foreground: Option<String>,
+    normal: AnsiColors,
+    bright: AnsiColors,
+    dim: AnsiColors,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct AnsiColors {
+    black: Option<String>,
+    red: Option<String>,
+    green: Option<String>,
+    yellow: Option<String>,
+    blue: Option<String>,
+    magenta: Option<String>,
+    cyan: Option<String>,
+    white: Option<String>,
+}
+
+impl AnsiColors {
+    fn apply(&self, target: &mut [Rgb]) -> Result<(), String> {
+        let colors = [
+            &self.black, &self.red, &self.green, &self.yellow,
+            &self.blue, &self.magenta, &self.cyan, &self.white,
+        ];
+        for (slot, color) in target.iter_mut().zip(colors) {
+            if let Some(color) = color {
+                *slot = parse_hex(color)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        [
+            &self.black, &self.red, &self.green, &self.yellow,
+            &self.blue, &self.magenta, &self.cyan, &self.white,
+        ]
+        .iter()
+        .all(|c| c.is_none())
+    }
+}
+
+impl ThemeFile {
+    fn into_theme(self, fallback_name: String) -> Result<Theme, String> {
+        let base_name = self.base.as_deref().unwrap_or("dark");
+        let mut theme = Theme::builtin(base_name)
+            .ok_or_else(|| format!("unknown base theme `{}`", base_name))?;
+        theme.name = self.name.unwrap_or(fallback_name);
+
+        let parse = |value: &Option<String>| value.as_deref().map(parse_hex).transpose();
+        if let Some(color) = parse(&self.foreground)? {
+            theme.foreground = color;
+        }
+        if let Some(color) = parse(&self.background)? {
+            theme.background = color;
+            theme.cursor_text = color;
+        }
+        if let Some(color) = parse(&self.cursor)? {
+            theme.cursor = color;
+        }
+        if let Some(color) = parse(&self.cursor_text)? {
+            theme.cursor_text = color;
+        }
+        if let Some(color) = parse(&self.selection_background)? {
+            theme.selection_background = color;
+        }
+        theme.selection_foreground = parse(&self.selection_foreground)?;
+
+        let mut ansi = [Rgb::default(); 16];
+        ansi.copy_from_slice(&theme.palette[..16]);
+        self.normal.apply(&mut ansi[..8])?;
+        self.bright.apply(&mut ansi[8..])?;
+
+        let dim = if self.dim.is_empty() {
+            None
+        } else {
+            let mut dim = [Rgb::default(); 8];
+            for (i, slot) in dim.iter_mut().enumerate() {
+                *slot = scale(ansi[i], DIM_FACTOR);
+            }
+            self.dim.apply(&mut dim)?;
+            Some(dim)
+        };
+        let bright_foreground = parse(&self.bright_foreground)?;
+
+        theme.rebuild_palette(&ansi, dim.as_ref(), bright_foreground);
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn gray(value: u8) -> Rgb {
+        Rgb { r: value, g: value, b: value }
+    }
+
+    #[test]
+    fn builtin_names() {
+        for name in BUILTIN_THEMES {
+            assert_eq!(Theme::builtin(name).map(|theme| theme.name), Some(name.to_string()));
+        }
+        assert_eq!(Theme::builtin("Solarized-Dark").map(|theme| theme.name), Some("solarized".to_string()));
+        assert!(Theme::builtin("nord").is_none());
+    }
+
+    #[test]
+    fn cube_and_grayscale_palette() {
+        let theme = Theme::default();
+        let colors = Colors::default();
+        assert_eq!(theme.lookup(16, &colors), gray(0));
+        assert_eq!(theme.lookup(17, &colors), Rgb { r: 0, g: 0, b: 95 });
+        assert_eq!(theme.lookup(196, &colors), Rgb { r: 255, g: 0, b: 0 });
+        assert_eq!(theme.lookup(231, &colors), gray(255));
+        assert_eq!(theme.lookup(232, &colors), gray(8));
+        assert_eq!(theme.lookup(255, &colors), gray(238));
+        assert_eq!(theme.lookup(NamedColor::Background as usize, &colors), rgb(0x1e1e1e));
+    }
+
+    #[test]
+    fn overrides_win_over_the_palette() {
+        let theme = Theme::default();
+        let mut colors = Colors::default();
+        colors[1] = Some(rgb(0x123456));
+        assert_eq!(theme.lookup(1, &colors), rgb(0x123456));
+        assert_eq!(theme.resolve(Color::Indexed(1), Flags::empty(), &colors), to_vec4(rgb(0x123456)));
+    }
+
+    #[test]
+    fn custom_theme_file() {
+        let source = r##"
+base = "light"
+background = "#101010"
+[normal]
+red = "#ff0000"
+[dim]
+red = "#800000"
+"##;
+        let theme = Theme::parse(source, "mine".to_string()).unwrap();
+        let colors = Colors::default();
+        assert_eq!(theme.name, "mine");
+        assert_eq!(theme.background, rgb(0x101010));
+        assert_eq!(theme.cursor_text, rgb(0x101010));
+        assert_eq!(theme.foreground, Theme::light().foreground);
+        assert_eq!(theme.lookup(1, &colors), rgb(0xff0000));
+        assert_eq!(theme.lookup(NamedColor::DimRed as usize, &colors), rgb(0x800000));
+        // Untouched entries keep the base theme's colors
+        assert_eq!(theme.lookup(9, &colors), Theme::light().lookup(9, &colors));
+    }
+
+    #[test]
+    fn malformed_theme_files_are_rejected() {
+        let cases = [
+            "foreground = \"#12345\"",
+            "foreground = \"#gggggg\"",
+            "base = \"nord\"",
+            "[normal]\npurple = \"#ffffff\"",
+            "colour = \"#ffffff\"",
+            "foreground = ",
+        ];
+        for source in cases {
+            assert!(Theme::parse(source, "broken".to_string()).is_err(), "{}", source);
+        }
+    }
+}
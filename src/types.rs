@@ -1,16 +1,19 @@
 // ModernTerm - Types Module
 // Based on tterm's proven architecture with Makepad integration
 
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
 // Re-export makepad types we'll use
 pub use makepad_widgets::*;
 
 /// View mode for terminal display
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub enum ViewMode {
     /// Single terminal view (with tabs)
+    #[default]
     Single,
     /// Grid view with dynamic layout
     Grid { 
@@ -23,21 +26,40 @@ pub enum ViewMode {
     },
 }
 
-impl Default for ViewMode {
-    fn default() -> Self {
-        ViewMode::Single
-    }
-}
-
 /// Direction for splitting panels
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SplitDirection {
+    /// Horizontal divider: panes stacked top and bottom
     Horizontal,
+    /// Vertical divider: panes side by side
     Vertical,
 }
 
+/// Screen direction for pane navigation and keyboard resizing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl PaneDirection {
+    /// The split whose divider moves along this direction
+    pub fn split_direction(self) -> SplitDirection {
+        match self {
+            PaneDirection::Left | PaneDirection::Right => SplitDirection::Vertical,
+            PaneDirection::Up | PaneDirection::Down => SplitDirection::Horizontal,
+        }
+    }
+}
+
+/// Split ratios are kept in this range so no pane collapses to nothing
+pub const MIN_SPLIT_RATIO: f32 = 0.1;
+pub const MAX_SPLIT_RATIO: f32 = 0.9;
+
 /// Recursive panel content structure (proven in tterm)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PanelContent {
     /// Single terminal panel
     Terminal(u64), // terminal_id
@@ -50,11 +72,192 @@ pub enum PanelContent {
     },
 }
 
+impl PanelContent {
+    /// Replace the leaf `target` with a split of it and `new_terminal` (new pane second)
+    pub fn split_terminal(&mut self, target: u64, direction: SplitDirection, new_terminal: u64) -> bool {
+        match self {
+            PanelContent::Terminal(id) if *id == target => {
+                *self = PanelContent::Split {
+                    direction,
+                    first: Box::new(PanelContent::Terminal(target)),
+                    second: Box::new(PanelContent::Terminal(new_terminal)),
+                    ratio: 0.5,
+                };
+                true
+            }
+            PanelContent::Terminal(_) => false,
+            PanelContent::Split { first, second, .. } => {
+                first.split_terminal(target, direction, new_terminal)
+                    || second.split_terminal(target, direction, new_terminal)
+            }
+        }
+    }
+
+    /// Remove the leaf `target` and promote its sibling into the parent's slot.
+    ///
+    /// Returns the sibling's first terminal (the pane to focus next), or None if
+    /// `target` isn't in the tree or is the root itself.
+    pub fn remove_terminal(&mut self, target: u64) -> Option<u64> {
+        let PanelContent::Split { first, second, .. } = self else {
+            return None;
+        };
+        let is_target = |node: &PanelContent| matches!(node, PanelContent::Terminal(id) if *id == target);
+        let sibling = if is_target(first) {
+            Some(std::mem::replace(second.as_mut(), PanelContent::Terminal(target)))
+        } else if is_target(second) {
+            Some(std::mem::replace(first.as_mut(), PanelContent::Terminal(target)))
+        } else {
+            None
+        };
+        match sibling {
+            Some(sibling) => {
+                *self = sibling;
+                Some(self.first_terminal())
+            }
+            None => first.remove_terminal(target).or_else(|| second.remove_terminal(target)),
+        }
+    }
+
+    /// Leftmost/topmost terminal of the tree
+    pub fn first_terminal(&self) -> u64 {
+        match self {
+            PanelContent::Terminal(id) => *id,
+            PanelContent::Split { first, .. } => first.first_terminal(),
+        }
+    }
+
+    /// The node at a path of child indices (0 = first, 1 = second)
+    pub fn node_at_mut(&mut self, path: &[usize]) -> Option<&mut PanelContent> {
+        let Some((&index, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        match self {
+            PanelContent::Terminal(_) => None,
+            PanelContent::Split { first, second, .. } => {
+                let child = if index == 0 { first } else { second };
+                child.node_at_mut(rest)
+            }
+        }
+    }
+
+    /// Set the ratio of the split at `path`, clamped to the safe range
+    pub fn set_ratio(&mut self, path: &[usize], new_ratio: f32) -> bool {
+        match self.node_at_mut(path) {
+            Some(PanelContent::Split { ratio, .. }) => {
+                *ratio = new_ratio.clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Move the divider of the innermost `direction` split around `target` by `delta`
+    pub fn adjust_ratio_around(&mut self, target: u64, direction: SplitDirection, delta: f32) -> bool {
+        self.adjust_ratio(target, direction, delta) == Some(true)
+    }
+
+    /// None: target not in this subtree; Some(adjusted)
+    fn adjust_ratio(&mut self, target: u64, wanted: SplitDirection, delta: f32) -> Option<bool> {
+        match self {
+            PanelContent::Terminal(id) => (*id == target).then_some(false),
+            PanelContent::Split { direction, first, second, ratio } => {
+                let adjusted = first
+                    .adjust_ratio(target, wanted, delta)
+                    .or_else(|| second.adjust_ratio(target, wanted, delta))?;
+                if adjusted || *direction != wanted {
+                    return Some(adjusted);
+                }
+                *ratio = (*ratio + delta).clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO);
+                Some(true)
+            }
+        }
+    }
+
+    /// Give every pane in a run of same-direction splits the same share
+    pub fn equalize(&mut self) {
+        if let PanelContent::Split { direction, first, second, ratio } = self {
+            let first_weight = first.weight(*direction);
+            let second_weight = second.weight(*direction);
+            // No MIN/MAX clamp: equal shares of more than ten panes fall below MIN_SPLIT_RATIO
+            *ratio = first_weight / (first_weight + second_weight);
+            first.equalize();
+            second.equalize();
+        }
+    }
+
+    /// Number of panes laid out next to each other along `direction`
+    fn weight(&self, along: SplitDirection) -> f32 {
+        match self {
+            PanelContent::Split { direction, first, second, .. } if *direction == along => {
+                first.weight(along) + second.weight(along)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// All terminal ids in the tree, in layout order
+    pub fn terminal_ids(&self) -> Vec<u64> {
+        match self {
+            PanelContent::Terminal(id) => vec![*id],
+            PanelContent::Split { first, second, .. } => {
+                let mut ids = first.terminal_ids();
+                ids.extend(second.terminal_ids());
+                ids
+            }
+        }
+    }
+
+    /// Copy of the tree with every terminal id replaced (splits keep direction and ratio)
+    pub fn map_terminals(&self, f: &mut impl FnMut(u64) -> u64) -> PanelContent {
+        match self {
+            PanelContent::Terminal(id) => PanelContent::Terminal(f(*id)),
+            PanelContent::Split { direction, first, second, ratio } => PanelContent::Split {
+                direction: *direction,
+                first: Box::new(first.map_terminals(f)),
+                second: Box::new(second.map_terminals(f)),
+                ratio: *ratio,
+            },
+        }
+    }
+}
+
+/// What taking a pane out of its tab did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneRemoval {
+    /// The pane's sibling took over the parent split's space
+    Collapsed { tab_id: u64, sibling: u64 },
+    /// It was the only pane, so the whole tab has to close
+    LastInTab(u64),
+}
+
+/// A closed tab remembered so it can be reopened
+#[derive(Debug, Clone)]
+pub struct ClosedTab {
+    pub title: String,
+    /// Name the user gave the tab, if any
+    pub custom_title: Option<String>,
+    /// Index the tab had in tab_order
+    pub position: usize,
+    /// Split tree with the old terminal ids; new shells are spawned in their place
+    pub layout: PanelContent,
+    /// Working directory of each old terminal
+    pub directories: HashMap<u64, PathBuf>,
+}
+
+/// Seconds between tab title/directory refreshes while terminals produce output
+pub const TAB_INFO_INTERVAL: f64 = 0.5;
+
+/// How many closed tabs "reopen closed tab" can bring back
+pub const MAX_CLOSED_TABS: usize = 10;
+
 /// Terminal tab representation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalTab {
     pub id: u64,
-    pub title: String,
+    pub title: String,  // Follows OSC 0/2 titles and the foreground process
+    pub custom_title: Option<String>,  // Set by renaming the tab; locks the title
+    #[serde(default)]
+    pub bell: bool,  // A background pane rang the bell since the tab was last active
     pub current_directory: Option<String>,
     pub process_name: Option<String>,
     pub input_buffer: String,  // Track current input line
@@ -62,6 +265,13 @@ pub struct TerminalTab {
     pub output_lines: Vec<String>,  // Terminal output lines
 }
 
+impl TerminalTab {
+    /// Title shown in the tab strip: the user's name for the tab, or the live title
+    pub fn display_title(&self) -> &str {
+        self.custom_title.as_deref().unwrap_or(&self.title)
+    }
+}
+
 /// Size representation for layout calculations
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Size {
@@ -115,6 +325,9 @@ impl KoreanInputState {
     }
 }
 
+pub use crate::keymap::{AppAction, Keymap};
+pub use crate::theme::Theme;
+
 // Import the real terminal backend
 pub use crate::terminal::{TerminalBackend, BackendSettings};
 
@@ -128,6 +341,10 @@ pub struct AppState {
     pub tab_order: Vec<u64>, // Maintain tab order
     pub active_tab_id: u64,
     pub next_tab_id: u64,
+    // Tabs in order of use, most recent last (for "last used tab" toggling)
+    pub tab_history: Vec<u64>,
+    // Recently closed tabs, most recent last
+    pub closed_tabs: Vec<ClosedTab>,
     
     // Terminal backends
     pub terminals: HashMap<u64, TerminalBackend>, // All terminal backends
@@ -136,7 +353,13 @@ pub struct AppState {
     
     // View state
     pub view_mode: ViewMode,
+    // Grid column/row ratios last used for each (rows, cols) shape, kept across toggles
+    pub saved_grid_ratios: HashMap<(usize, usize), (Vec<f32>, Vec<f32>)>,
     pub focused_terminal: Option<u64>,
+    // Pane focused before the current one, for "previous pane" toggling
+    pub previous_focused_terminal: Option<u64>,
+    // Zoomed pane per tab (tab_id -> terminal_id); the split tree is kept as is
+    pub zoomed_panes: HashMap<u64, u64>,
     
     // Broadcasting system (proven in tterm)
     pub broadcast_mode: bool,
@@ -150,6 +373,26 @@ pub struct AppState {
     
 
     
+    // User configuration (loaded from config.toml, reloaded on change)
+    pub config: AppConfig,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    // Whether the window has keyboard focus (unfocused cursors are drawn hollow)
+    pub window_focused: bool,
+    // Transient message shown in the status bar (config errors etc.)
+    pub status_message: Option<String>,
+    // A Ctrl+V paste chord was handled; the platform delivers the clipboard as TextInput
+    pub paste_pending: bool,
+    // Multi-line paste waiting for a second paste to confirm it
+    pub paste_confirm: Option<String>,
+    // Tab being renamed and the name typed so far
+    pub tab_rename: Option<(u64, String)>,
+    
+    // Debounces PTY resizes while a split divider is dragged or nudged
+    pub pane_resize_timer: Timer,
+    // Throttles the /proc reads behind tab titles and directories during output
+    pub tab_info_timer: Timer,
+    
     // Initialization state
     pub initialized: bool,
     pub pty_thread_started: bool,
@@ -165,16 +408,31 @@ impl AppState {
             tab_order: Vec::new(),
             active_tab_id: 0,
             next_tab_id: 1,
+            tab_history: Vec::new(),
+            closed_tabs: Vec::new(),
             terminals: HashMap::new(),
             next_terminal_id: 1,
             tab_layouts: HashMap::new(),
             view_mode: ViewMode::Single,
+            saved_grid_ratios: HashMap::new(),
             focused_terminal: None,
+            previous_focused_terminal: None,
+            zoomed_panes: HashMap::new(),
             broadcast_mode: false,
             selected_terminals: HashSet::new(),
             korean_input_states: HashMap::new(),
             follow_output: HashMap::new(),
             scroll_offset: HashMap::new(),
+            config: AppConfig::default(),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            window_focused: true,
+            status_message: None,
+            paste_pending: false,
+            paste_confirm: None,
+            tab_rename: None,
+            pane_resize_timer: Timer::default(),
+            tab_info_timer: Timer::empty(),
             initialized: false,
             pty_thread_started: false,
         }
@@ -182,14 +440,25 @@ impl AppState {
     
     /// Create a new terminal instance
     pub fn create_terminal(&mut self, signal: SignalToUI) -> u64 {
+        self.create_terminal_in(signal, None)
+    }
+    
+    /// Create a new terminal whose shell starts in `working_directory`
+    pub fn create_terminal_in(&mut self, signal: SignalToUI, working_directory: Option<PathBuf>) -> u64 {
         let terminal_id = self.next_terminal_id;
         self.next_terminal_id += 1;
         
+        let settings = BackendSettings {
+            scrollback_lines: self.config.behavior.scrollback_lines,
+            working_directory,
+            ..BackendSettings::default()
+        };
+        
         // Create terminal backend with alacritty_terminal
         match TerminalBackend::new(
             terminal_id,
             signal,
-            BackendSettings::default(),
+            settings,
         ) {
             Ok(terminal_backend) => {
                 self.terminals.insert(terminal_id, terminal_backend);
@@ -226,6 +495,288 @@ impl AppState {
         }
     }
     
+    /// Move keyboard focus to a pane, remembering the previous one
+    pub fn focus_terminal(&mut self, terminal_id: u64) {
+        if self.focused_terminal != Some(terminal_id) {
+            self.previous_focused_terminal = self.focused_terminal;
+            self.focused_terminal = Some(terminal_id);
+        }
+        // In grid view the pane may belong to another tab
+        if let Some(tab_id) = self.tab_of_terminal(terminal_id) {
+            self.set_active_tab(tab_id);
+        }
+    }
+    
+    /// Make a tab active and focus its zoomed pane (or its first pane)
+    pub fn activate_tab(&mut self, tab_id: u64) {
+        let Some(layout) = self.tab_layouts.get(&tab_id) else {
+            return;
+        };
+        let terminal_id = self
+            .zoomed_panes
+            .get(&tab_id)
+            .copied()
+            .unwrap_or_else(|| layout.first_terminal());
+        if self.focused_terminal != Some(terminal_id) {
+            self.previous_focused_terminal = self.focused_terminal;
+            self.focused_terminal = Some(terminal_id);
+        }
+        self.set_active_tab(tab_id);
+    }
+    
+    /// Set the active tab and move it to the top of the use history
+    pub fn set_active_tab(&mut self, tab_id: u64) {
+        self.active_tab_id = tab_id;
+        if let Some(tab) = self.tabs.get_mut(&tab_id) {
+            tab.bell = false;
+        }
+        self.tab_history.retain(|&id| id != tab_id);
+        self.tab_history.push(tab_id);
+    }
+    
+    /// Pane a tab takes its title from: the focused one when it is in the tab, else the first
+    pub fn title_terminal(&self, tab_id: u64) -> Option<u64> {
+        let layout = self.tab_layouts.get(&tab_id)?;
+        let terminals = layout.terminal_ids();
+        match self.focused_terminal {
+            Some(focused) if terminals.contains(&focused) => Some(focused),
+            _ => terminals.first().copied(),
+        }
+    }
+    
+    /// Update tab titles from OSC 0/2, falling back to the foreground process name,
+    /// and each tab's current directory.
+    ///
+    /// Returns true if any title changed.
+    pub fn refresh_tab_info(&mut self) -> bool {
+        let mut changed = false;
+        for tab_id in self.tab_order.clone() {
+            let Some(backend) = self.title_terminal(tab_id).and_then(|id| self.terminals.get(&id)) else {
+                continue;
+            };
+            let process_name = crate::process::foreground_process_name(backend.pty_id());
+            let title = backend.title().or_else(|| process_name.clone());
+            let directory = backend.working_directory().and_then(|dir| dir.to_str().map(|s| s.to_string()));
+            let Some(tab) = self.tabs.get_mut(&tab_id) else {
+                continue;
+            };
+            tab.process_name = process_name.or(tab.process_name.take());
+            tab.current_directory = directory.or(tab.current_directory.take());
+            if let Some(title) = title.filter(|title| *title != tab.title) {
+                tab.title = title;
+                changed = true;
+            }
+        }
+        changed
+    }
+    
+    /// Start editing a tab's name, beginning with the title it shows now
+    pub fn start_tab_rename(&mut self, tab_id: u64) {
+        if let Some(tab) = self.tabs.get(&tab_id) {
+            self.tab_rename = Some((tab_id, tab.display_title().to_string()));
+        }
+    }
+    
+    /// The tab used before the active one
+    pub fn last_used_tab(&self) -> Option<u64> {
+        self.tab_history
+            .iter()
+            .rev()
+            .copied()
+            .find(|&id| id != self.active_tab_id && self.tabs.contains_key(&id))
+    }
+    
+    /// The working directory a terminal's shell was last known to be in
+    pub fn terminal_directory(&self, terminal_id: u64) -> Option<PathBuf> {
+        if let Some(dir) = self.terminals.get(&terminal_id).and_then(|backend| backend.working_directory()) {
+            return Some(dir);
+        }
+        let tab_id = self.tab_of_terminal(terminal_id)?;
+        self.tabs.get(&tab_id)?.current_directory.as_ref().map(PathBuf::from)
+    }
+    
+    /// Where a new tab or split opened from the focused terminal should start
+    pub fn new_terminal_directory(&self) -> Option<PathBuf> {
+        self.focused_terminal
+            .and_then(|id| self.terminal_directory(id))
+            .filter(|dir| dir.is_dir())
+    }
+    
+    /// The tab whose layout contains a terminal
+    pub fn tab_of_terminal(&self, terminal_id: u64) -> Option<u64> {
+        self.tab_layouts
+            .iter()
+            .find(|(_, layout)| layout.terminal_ids().contains(&terminal_id))
+            .map(|(&tab_id, _)| tab_id)
+    }
+    
+    /// Take a terminal's pane out of its tab's split tree (and zoom).
+    ///
+    /// None if no tab holds the terminal. The terminal backend itself stays.
+    pub fn detach_pane(&mut self, terminal_id: u64) -> Option<PaneRemoval> {
+        let tab_id = self.tab_of_terminal(terminal_id)?;
+        let layout = self.tab_layouts.get_mut(&tab_id)?;
+        if matches!(layout, PanelContent::Terminal(id) if *id == terminal_id) {
+            return Some(PaneRemoval::LastInTab(tab_id));
+        }
+        let sibling = layout.remove_terminal(terminal_id)?;
+        if self.zoomed_panes.get(&tab_id) == Some(&terminal_id) {
+            self.zoomed_panes.remove(&tab_id);
+        }
+        Some(PaneRemoval::Collapsed { tab_id, sibling })
+    }
+    
+    /// Tabs on screen: the active one, or every tab with a grid cell
+    pub fn visible_tabs(&self) -> Vec<u64> {
+        match &self.view_mode {
+            ViewMode::Single => vec![self.active_tab_id],
+            ViewMode::Grid { rows, cols, .. } => self.tab_order.iter().copied().take(rows * cols).collect(),
+        }
+    }
+    
+    /// Screen area of each visible tab within the terminal area
+    pub fn visible_tab_areas(&self, area: Rect) -> Vec<(u64, Rect)> {
+        let visible = self.visible_tabs();
+        crate::layout::tab_areas(&self.view_mode, &self.tab_order, area)
+            .into_iter()
+            .filter(|(tab_id, _)| visible.contains(tab_id))
+            .collect()
+    }
+    
+    /// Screen rectangle of every pane on screen
+    pub fn visible_pane_rects(&self, area: Rect) -> Vec<(u64, Rect)> {
+        self.visible_tab_areas(area)
+            .into_iter()
+            .filter_map(|(tab_id, tab_area)| Some(crate::layout::pane_rects(&*self.visible_layout(tab_id)?, tab_area)))
+            .flatten()
+            .collect()
+    }
+    
+    /// Terminals on screen (the ones worth syncing on PTY output)
+    pub fn visible_terminals(&self) -> Vec<u64> {
+        self.visible_tabs()
+            .into_iter()
+            .filter_map(|tab_id| self.visible_layout(tab_id))
+            .flat_map(|layout| layout.terminal_ids())
+            .collect()
+    }
+    
+    /// Whether several panes are on screen, so the focused one needs a frame
+    pub fn shows_multiple_panes(&self) -> bool {
+        matches!(self.view_mode, ViewMode::Grid { .. }) || self.active_tab_is_split()
+    }
+    
+    /// Leave grid view, remembering its ratios
+    pub fn set_single_view(&mut self) {
+        self.save_grid_ratios();
+        self.view_mode = ViewMode::Single;
+    }
+    
+    /// Remember the current grid's ratios for its shape
+    pub fn save_grid_ratios(&mut self) {
+        if let ViewMode::Grid { rows, cols, col_ratios, row_ratios } = &self.view_mode {
+            self.saved_grid_ratios.insert((*rows, *cols), (col_ratios.clone(), row_ratios.clone()));
+        }
+    }
+    
+    /// A grid view of this shape, with the ratios it had last time (uniform at first)
+    pub fn grid_view(&self, rows: usize, cols: usize) -> ViewMode {
+        let (col_ratios, row_ratios) = self
+            .saved_grid_ratios
+            .get(&(rows, cols))
+            .cloned()
+            .unwrap_or_else(|| (vec![1.0 / cols as f32; cols], vec![1.0 / rows as f32; rows]));
+        ViewMode::Grid { rows, cols, col_ratios, row_ratios }
+    }
+    
+    /// Drop a terminal's backend (shutting down its PTY) and all per-terminal state
+    pub fn remove_terminal(&mut self, terminal_id: u64) {
+        self.terminals.remove(&terminal_id);
+        self.korean_input_states.remove(&terminal_id);
+        self.follow_output.remove(&terminal_id);
+        self.scroll_offset.remove(&terminal_id);
+        if self.selected_terminals.remove(&terminal_id) {
+            self.end_broadcast_if_unselected("the last selected pane was closed");
+        }
+        if self.previous_focused_terminal == Some(terminal_id) {
+            self.previous_focused_terminal = None;
+        }
+    }
+    
+    /// Terminals that receive typed and pasted input: the focused one, plus every
+    /// broadcast target while broadcast mode is on
+    pub fn input_recipients(&self) -> Vec<u64> {
+        let mut recipients: Vec<u64> = self.focused_terminal.into_iter().collect();
+        if self.broadcast_mode {
+            let mut targets: Vec<u64> = self
+                .terminals
+                .keys()
+                .copied()
+                .filter(|&id| self.is_broadcast_target(id))
+                .collect();
+            targets.sort_unstable();
+            recipients.extend(targets.into_iter().filter(|id| Some(*id) != self.focused_terminal));
+        }
+        recipients
+    }
+
+    /// Whether a terminal gets broadcast input (the selected ones, or all when none are selected)
+    pub fn is_broadcast_target(&self, terminal_id: u64) -> bool {
+        self.broadcast_mode && (self.selected_terminals.is_empty() || self.selected_terminals.contains(&terminal_id))
+    }
+
+    /// Add a terminal to the broadcast selection, or take it out again
+    pub fn toggle_broadcast_selection(&mut self, terminal_id: u64) {
+        if self.selected_terminals.remove(&terminal_id) {
+            self.end_broadcast_if_unselected("no panes are selected");
+        } else {
+            self.selected_terminals.insert(terminal_id);
+        }
+    }
+
+    /// An empty selection means "all terminals", so emptying a selection must not
+    /// widen the broadcast to every pane: turn broadcast off instead
+    fn end_broadcast_if_unselected(&mut self, reason: &str) {
+        if self.broadcast_mode && self.selected_terminals.is_empty() {
+            self.broadcast_mode = false;
+            self.status_message = Some(format!("📡 Broadcast off: {}", reason));
+            ::log::info!("📡 Broadcast off: {}", reason);
+        }
+    }
+
+    /// What a tab shows: its split tree, or just the zoomed pane
+    pub fn visible_layout(&self, tab_id: u64) -> Option<Cow<'_, PanelContent>> {
+        let layout = self.tab_layouts.get(&tab_id)?;
+        match self.zoomed_panes.get(&tab_id) {
+            Some(&terminal_id) => Some(Cow::Owned(PanelContent::Terminal(terminal_id))),
+            None => Some(Cow::Borrowed(layout)),
+        }
+    }
+    
+    /// Whether the active tab currently shows more than one pane
+    pub fn active_tab_is_split(&self) -> bool {
+        self.visible_layout(self.active_tab_id)
+            .is_some_and(|layout| matches!(*layout, PanelContent::Split { .. }))
+    }
+    
+    /// Whether the active tab has a zoomed pane
+    pub fn active_tab_is_zoomed(&self) -> bool {
+        self.zoomed_panes.contains_key(&self.active_tab_id)
+    }
+    
+    /// Resize the panes' PTYs shortly after the last layout change
+    pub fn schedule_pane_resize(&mut self, cx: &mut Cx) {
+        cx.stop_timer(self.pane_resize_timer);
+        self.pane_resize_timer = cx.start_timeout(0.1);
+    }
+    
+    /// Refresh tab titles and directories soon; repeated calls until then are free
+    pub fn schedule_tab_info_refresh(&mut self, cx: &mut Cx) {
+        if self.tab_info_timer.is_empty() {
+            self.tab_info_timer = cx.start_timeout(TAB_INFO_INTERVAL);
+        }
+    }
+    
     pub fn is_initialized(&self) -> bool {
         self.initialized
     }
@@ -242,56 +793,290 @@ impl Default for AppState {
 }
 
 /// Application configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Every section falls back to its defaults, so a config file only needs the keys it overrides.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub appearance: AppearanceConfig,
     pub behavior: BehaviorConfig,
     pub keyboard: KeyboardConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppearanceConfig {
     pub font_family: String,
     pub font_size: f32,
     pub theme: String,
+    /// Not applied yet: the window is always opaque
     pub opacity: f32,
+    /// Cursor blink interval in milliseconds for blinking cursor styles (0 = never blink)
+    pub cursor_blink_interval: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl AppearanceConfig {
+    /// Monospace cell size of the drawn face at the font size (4/3 pt->px)
+    pub fn cell_size(&self) -> Size {
+        let metrics = FontMetrics::D2CODING;
+        let pixels = self.font_size.max(1.0) * 4.0 / 3.0;
+        Size {
+            width: (pixels * metrics.advance).round().max(1.0),
+            height: (pixels * metrics.line_height).round().max(1.0),
+        }
+    }
+}
+
+/// The only face the terminal view draws with (D2_CODING_FONT in terminal_view.rs)
+pub const BUNDLED_FONT_FAMILY: &str = "D2Coding";
+
+/// Advance width and line height of a monospace face, in ems
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    pub advance: f32,
+    pub line_height: f32,
+}
+
+impl FontMetrics {
+    /// The bundled face glyphs are drawn with
+    pub const D2CODING: Self = Self { advance: 0.5, line_height: 1.0 };
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BehaviorConfig {
     pub scrollback_lines: usize,
     pub close_tab_on_exit: bool,
     pub confirm_quit: bool,
+    /// Copy the selection to the clipboard as soon as the mouse is released
+    pub copy_on_select: bool,
+    /// Ask for confirmation before pasting line breaks into a program without bracketed paste
+    pub warn_multiline_paste: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct KeyboardConfig {
     pub new_tab: String,
     pub close_tab: String,
     pub split_vertical: String,
     pub split_horizontal: String,
+    /// Extra chord -> action bindings, e.g. "Ctrl+R" = "Passthrough"
+    pub bindings: BTreeMap<String, String>,
 }
 
-impl Default for AppConfig {
+impl Default for AppearanceConfig {
     fn default() -> Self {
         Self {
-            appearance: AppearanceConfig {
-                font_family: "JetBrains Mono".to_string(),
-                font_size: 14.0,
-                theme: "dark".to_string(),
-                opacity: 0.95,
-            },
-            behavior: BehaviorConfig {
-                scrollback_lines: 10000,
-                close_tab_on_exit: true,
-                confirm_quit: true,
-            },
-            keyboard: KeyboardConfig {
-                new_tab: "Ctrl+T".to_string(),
-                close_tab: "Ctrl+W".to_string(),
-                split_vertical: "Ctrl+Shift+V".to_string(),
-                split_horizontal: "Ctrl+Shift+H".to_string(),
-            },
+            font_family: BUNDLED_FONT_FAMILY.to_string(),
+            font_size: 14.0,
+            theme: "dark".to_string(),
+            opacity: 0.95,
+            cursor_blink_interval: 530,
+        }
+    }
+}
+
+impl Default for BehaviorConfig {
+    fn default() -> Self {
+        Self {
+            scrollback_lines: 10000,
+            close_tab_on_exit: true,
+            confirm_quit: true,
+            copy_on_select: true,
+            warn_multiline_paste: true,
         }
     }
 }
+
+impl Default for KeyboardConfig {
+    fn default() -> Self {
+        Self {
+            new_tab: "Ctrl+T".to_string(),
+            close_tab: "Ctrl+W".to_string(),
+            // Ctrl+Shift+V is paste
+            split_vertical: "Ctrl+Shift+E".to_string(),
+            split_horizontal: "Ctrl+Shift+H".to_string(),
+            bindings: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V: SplitDirection = SplitDirection::Vertical;
+    const H: SplitDirection = SplitDirection::Horizontal;
+
+    fn leaf(id: u64) -> PanelContent {
+        PanelContent::Terminal(id)
+    }
+
+    fn split(direction: SplitDirection, first: PanelContent, second: PanelContent, ratio: f32) -> PanelContent {
+        PanelContent::Split { direction, first: Box::new(first), second: Box::new(second), ratio }
+    }
+
+    #[test]
+    fn split_replaces_the_target_leaf() {
+        let mut layout = leaf(1);
+        assert!(layout.split_terminal(1, V, 2));
+        assert!(layout.split_terminal(2, H, 3));
+        assert_eq!(layout, split(V, leaf(1), split(H, leaf(2), leaf(3), 0.5), 0.5));
+        assert_eq!(layout.terminal_ids(), vec![1, 2, 3]);
+
+        assert!(!layout.split_terminal(9, V, 4));
+        assert_eq!(layout.terminal_ids(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn removing_a_child_collapses_its_split() {
+        // 1 | (2 / 3): closing 2 promotes 3 into the inner split's slot
+        let mut layout = split(V, leaf(1), split(H, leaf(2), leaf(3), 0.3), 0.6);
+        assert_eq!(layout.remove_terminal(2), Some(3));
+        assert_eq!(layout, split(V, leaf(1), leaf(3), 0.6));
+
+        // Closing a first child promotes a whole subtree and focuses its first pane
+        let mut layout = split(V, leaf(1), split(H, leaf(2), leaf(3), 0.3), 0.6);
+        assert_eq!(layout.remove_terminal(1), Some(2));
+        assert_eq!(layout, split(H, leaf(2), leaf(3), 0.3));
+
+        assert_eq!(layout.remove_terminal(3), Some(2));
+        assert_eq!(layout, leaf(2));
+    }
+
+    #[test]
+    fn the_last_leaf_and_unknown_ids_are_not_removed() {
+        let mut layout = leaf(1);
+        assert_eq!(layout.remove_terminal(1), None);
+        assert_eq!(layout, leaf(1));
+
+        let mut layout = split(V, leaf(1), leaf(2), 0.5);
+        assert_eq!(layout.remove_terminal(7), None);
+        assert_eq!(layout, split(V, leaf(1), leaf(2), 0.5));
+    }
+
+    #[test]
+    fn ratios_are_clamped() {
+        let mut layout = split(V, leaf(1), split(H, leaf(2), leaf(3), 0.5), 0.5);
+        assert!(layout.set_ratio(&[], 0.0));
+        assert!(layout.set_ratio(&[1], 2.0));
+        assert_eq!(layout, split(V, leaf(1), split(H, leaf(2), leaf(3), MAX_SPLIT_RATIO), MIN_SPLIT_RATIO));
+        // Paths must end at a split
+        assert!(!layout.set_ratio(&[0], 0.5));
+        assert!(!layout.set_ratio(&[1, 0], 0.5));
+
+        let mut layout = split(V, leaf(1), leaf(2), 0.85);
+        assert!(layout.adjust_ratio_around(1, V, 0.1));
+        assert_eq!(layout, split(V, leaf(1), leaf(2), MAX_SPLIT_RATIO));
+        assert!(layout.adjust_ratio_around(2, V, -1.0));
+        assert_eq!(layout, split(V, leaf(1), leaf(2), MIN_SPLIT_RATIO));
+    }
+
+    #[test]
+    fn adjust_moves_the_innermost_matching_split() {
+        // 1 | (2 / (3 | 4))
+        let mut layout = split(V, leaf(1), split(H, leaf(2), split(V, leaf(3), leaf(4), 0.5), 0.5), 0.5);
+        assert!(layout.adjust_ratio_around(4, V, 0.1));
+        assert_eq!(layout, split(V, leaf(1), split(H, leaf(2), split(V, leaf(3), leaf(4), 0.6), 0.5), 0.5));
+
+        // No horizontal split around 1
+        assert!(!layout.adjust_ratio_around(1, H, 0.1));
+        // 2's nearest vertical split is the root
+        assert!(layout.adjust_ratio_around(2, V, -0.2));
+        assert_eq!(layout, split(V, leaf(1), split(H, leaf(2), split(V, leaf(3), leaf(4), 0.6), 0.5), 0.3));
+        assert!(!layout.adjust_ratio_around(9, V, 0.1));
+    }
+
+    #[test]
+    fn equalize_nested_splits() {
+        // Three panes side by side (1 | (2 | 3)) get a third each
+        let mut layout = split(V, leaf(1), split(V, leaf(2), leaf(3), 0.8), 0.7);
+        layout.equalize();
+        let PanelContent::Split { ratio, second, .. } = &layout else { panic!() };
+        assert!((ratio - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(**second, split(V, leaf(2), leaf(3), 0.5));
+
+        // A perpendicular split counts as one pane of its parent
+        let mut layout = split(V, split(H, leaf(1), leaf(2), 0.2), leaf(3), 0.9);
+        layout.equalize();
+        assert_eq!(layout, split(V, split(H, leaf(1), leaf(2), 0.5), leaf(3), 0.5));
+    }
+
+    #[test]
+    fn equalize_more_than_ten_panes_in_a_row() {
+        // 1 | (2 | (3 | ... 11)): every pane ends up with 1/11 of the width
+        let mut layout = leaf(11);
+        for id in (1..=10).rev() {
+            layout = split(V, leaf(id), layout, 0.5);
+        }
+        layout.equalize();
+
+        let mut remaining = 1.0;
+        let mut node = &layout;
+        while let PanelContent::Split { first, second, ratio, .. } = node {
+            assert!(matches!(**first, PanelContent::Terminal(_)));
+            assert!((remaining * ratio - 1.0 / 11.0).abs() < 1e-6);
+            remaining *= 1.0 - ratio;
+            node = second;
+        }
+        assert!((remaining - 1.0 / 11.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn detach_pane_in_a_background_tab() {
+        // Tab 1 (active) holds 10 | 11 with 11 zoomed, tab 2 only holds 20
+        let mut state = AppState::new();
+        state.tab_layouts.insert(1, split(V, leaf(10), leaf(11), 0.5));
+        state.tab_layouts.insert(2, leaf(20));
+        state.zoomed_panes.insert(1, 11);
+        state.active_tab_id = 2;
+
+        assert_eq!(state.detach_pane(11), Some(PaneRemoval::Collapsed { tab_id: 1, sibling: 10 }));
+        assert_eq!(state.tab_layouts[&1], leaf(10));
+        assert!(state.zoomed_panes.is_empty());
+
+        // Alone in its tab: the tab closes, the layout is left for close_tab
+        assert_eq!(state.detach_pane(10), Some(PaneRemoval::LastInTab(1)));
+        assert_eq!(state.detach_pane(20), Some(PaneRemoval::LastInTab(2)));
+        assert_eq!(state.tab_layouts[&1], leaf(10));
+        assert_eq!(state.detach_pane(99), None);
+    }
+
+    #[test]
+    fn emptying_the_broadcast_selection_ends_broadcast() {
+        let mut state = AppState::new();
+        state.broadcast_mode = true;
+
+        // Ctrl+click selects 1, a second Ctrl+click deselects it again
+        state.toggle_broadcast_selection(1);
+        assert!(state.broadcast_mode);
+        state.toggle_broadcast_selection(1);
+        assert!(!state.broadcast_mode);
+        assert!(state.status_message.is_some());
+
+        // Closing the only selected pane
+        state.broadcast_mode = true;
+        state.status_message = None;
+        state.toggle_broadcast_selection(2);
+        state.remove_terminal(2);
+        assert!(!state.broadcast_mode);
+        assert!(state.status_message.is_some());
+
+        // Closing one of several selected panes, or an unselected one, keeps broadcasting
+        state.broadcast_mode = true;
+        state.toggle_broadcast_selection(3);
+        state.toggle_broadcast_selection(4);
+        state.remove_terminal(3);
+        state.remove_terminal(5);
+        assert!(state.broadcast_mode);
+        assert!(state.is_broadcast_target(4));
+    }
+
+    #[test]
+    fn map_terminals_keeps_the_shape() {
+        let layout = split(V, leaf(1), split(H, leaf(2), leaf(3), 0.3), 0.6);
+        let mapped = layout.map_terminals(&mut |id| id * 10);
+        assert_eq!(mapped, split(V, leaf(10), split(H, leaf(20), leaf(30), 0.3), 0.6));
+    }
+}